@@ -0,0 +1,50 @@
+mod common;
+
+use common::bench;
+use matrix_builder::{Matrix, MatrixBlocky, MatrixColMajor, MatrixRowMajor};
+use std::hint::black_box;
+
+const N: usize = 256;
+
+fn main() {
+    let arr: Vec<Vec<i64>> = (0..N)
+        .map(|i| (0..N).map(|j| (i * N + j) as i64).collect())
+        .collect();
+    let row = MatrixRowMajor::new(&arr);
+    let col = MatrixColMajor::new(&arr);
+    let block = MatrixBlocky::new(&arr);
+
+    bench("row major get", || sum_checked(black_box(&row)));
+    bench("row major get_unchecked", || {
+        sum_unchecked(black_box(&row), MatrixRowMajor::get_unchecked)
+    });
+    bench("col major get", || sum_checked(black_box(&col)));
+    bench("col major get_unchecked", || {
+        sum_unchecked(black_box(&col), MatrixColMajor::get_unchecked)
+    });
+    bench("blocky get", || sum_checked(black_box(&block)));
+    bench("blocky get_unchecked", || {
+        sum_unchecked(black_box(&block), MatrixBlocky::get_unchecked)
+    });
+}
+
+fn sum_checked<M: Matrix<i64>>(m: &M) -> i64 {
+    let mut sum = 0;
+    for i in 0..m.rows() {
+        for j in 0..m.cols() {
+            sum += m.get(i, j).unwrap();
+        }
+    }
+    sum
+}
+
+fn sum_unchecked<M: Matrix<i64>>(m: &M, read: unsafe fn(&M, usize, usize) -> i64) -> i64 {
+    let mut sum = 0;
+    for i in 0..m.rows() {
+        for j in 0..m.cols() {
+            // SAFETY: i < rows and j < cols
+            sum += unsafe { read(m, i, j) };
+        }
+    }
+    sum
+}
@@ -0,0 +1,21 @@
+use std::time::{Duration, Instant};
+
+/// runs `f` until about half a second is spent and prints the mean time of one run
+pub fn bench<R>(name: &str, mut f: impl FnMut() -> R) {
+    // warm up, and find out how many runs fit in the time budget
+    let start = Instant::now();
+    std::hint::black_box(f());
+    let once = start.elapsed().max(Duration::from_nanos(1));
+    let runs = (Duration::from_millis(500).as_nanos() / once.as_nanos()).clamp(1, 1_000_000) as u32;
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        std::hint::black_box(f());
+    }
+    println!(
+        "{:<48} {:>12.2?} ({} runs)",
+        name,
+        start.elapsed() / runs,
+        runs
+    );
+}
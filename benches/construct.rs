@@ -0,0 +1,53 @@
+mod common;
+
+use common::bench;
+use matrix_builder::{Matrix, MatrixBlocky, MatrixColMajor, MatrixRowMajor};
+use std::hint::black_box;
+
+const N: usize = 512;
+
+fn main() {
+    let arr: Vec<Vec<i64>> = (0..N)
+        .map(|i| (0..N).map(|j| (i * N + j) as i64).collect())
+        .collect();
+
+    // what `new` did before it reserved the whole buffer up front
+    bench("row major collect", || {
+        black_box(&arr)
+            .iter()
+            .flat_map(|inner| inner.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    bench("row major new", || MatrixRowMajor::new(black_box(&arr)));
+    bench("col major collect", || {
+        let arr = black_box(&arr);
+        (0..N)
+            .flat_map(|j| arr.iter().map(move |inner| inner[j]))
+            .collect::<Vec<_>>()
+    });
+    bench("col major new", || MatrixColMajor::new(black_box(&arr)));
+    // what `new` did before it copied the rows straight into the blocks
+    bench("blocky nested quarters", || {
+        nested_quarters(black_box(&arr))
+    });
+    bench("blocky new", || MatrixBlocky::new(black_box(&arr)));
+}
+
+/// the four blocks the way `MatrixBlocky::new` used to build them, every quarter collected
+/// row by row into its own `Vec<Vec<T>>` first and only then flattened by `new`
+fn nested_quarters(arr: &[Vec<i64>]) -> Vec<MatrixRowMajor<i64>> {
+    let (top, left) = (arr.len().div_ceil(2), arr[0].len().div_ceil(2));
+    let quarter = |rows: &[Vec<i64>], cols: std::ops::Range<usize>| {
+        let nested: Vec<Vec<i64>> = rows.iter().map(|row| row[cols.clone()].to_vec()).collect();
+        MatrixRowMajor::new(&nested)
+    };
+    let (upper, lower) = arr.split_at(top);
+    let cols = arr[0].len();
+    vec![
+        quarter(upper, 0..left),
+        quarter(upper, left..cols),
+        quarter(lower, 0..left),
+        quarter(lower, left..cols),
+    ]
+}
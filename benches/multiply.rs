@@ -0,0 +1,40 @@
+mod common;
+
+use common::bench;
+use matrix_builder::{Matrix, MatrixBlocky, MatrixColMajor, MatrixRowMajor};
+
+fn main() {
+    for n in [32, 128, 512] {
+        let lhs = MatrixRowMajor::from_fn(n, n, |i, j| ((i * 7 + j * 3) % 11) as i64);
+        let rhs = MatrixRowMajor::from_fn(n, n, |i, j| ((i * 5 + j) % 13) as i64);
+
+        bench(&format!("row major mul {}x{}", n, n), || lhs.mul(&rhs));
+        #[cfg(feature = "parallel")]
+        bench(&format!("row major par_mul {}x{}", n, n), || {
+            lhs.par_mul(&rhs)
+        });
+
+        let (col_lhs, col_rhs) = (
+            MatrixColMajor::from(lhs.clone()),
+            MatrixColMajor::from(rhs.clone()),
+        );
+        bench(&format!("col major mul {}x{}", n, n), || {
+            col_lhs.mul(&col_rhs)
+        });
+
+        let lhs = MatrixBlocky::from(lhs);
+        let rhs = MatrixBlocky::from(rhs);
+        bench(&format!("blocky mul {}x{}", n, n), || lhs.mul(&rhs));
+    }
+
+    let n = 512;
+    let col = MatrixColMajor::from_fn(n, n, |i, j| ((i * 7 + j * 3) % 11) as i64);
+    let v: Vec<i64> = (0..n).map(|j| (j % 5) as i64).collect();
+    // the generic trait path, one dot product per row going through `get`
+    bench(&format!("col major generic matvec {}x{}", n, n), || {
+        (0..n)
+            .map(|i| (0..n).map(|j| col.get(i, j).unwrap() * v[j]).sum::<i64>())
+            .collect::<Vec<_>>()
+    });
+    bench(&format!("col major matvec {}x{}", n, n), || col.matvec(&v));
+}
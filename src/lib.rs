@@ -0,0 +1,6948 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Neg, Range, Sub, SubAssign};
+use std::str::FromStr;
+#[cfg(feature = "parallel")]
+use std::thread;
+
+pub trait Matrix<T>: Sized {
+    #[doc = r"default method for making a empty array"]
+    fn default() -> Self;
+    #[doc = r"this makes a new array by type T"]
+    ///
+    /// pass the argument by a vec
+    ///
+    fn new(slice: &[Vec<T>]) -> Self;
+    /// fallible version of `new` that rejects jagged rows
+    fn try_new(slice: &[Vec<T>]) -> Result<Self, MatrixError>;
+    /// builds the matrix from any iterator of rows, they all need the same length
+    fn from_rows<I: IntoIterator<Item = Vec<T>>>(iter: I) -> Result<Self, MatrixError> {
+        let rows: Vec<Vec<T>> = iter.into_iter().collect();
+        Self::try_new(&rows)
+    }
+    /// builds a `rows` x `cols` matrix where arr[i][j] is `f(i, j)`
+    fn from_fn(rows: usize, cols: usize, f: impl FnMut(usize, usize) -> T) -> Self;
+    #[allow(rustdoc::broken_intra_doc_links)]
+    #[doc = r"get method for returning the element in arr[i][j] position"]
+    fn get(&self, i: usize, j: usize) -> Result<T, MatrixError>;
+    /// element in arr[i][j] position, `None` when the position is not in the matrix
+    fn try_get(&self, i: usize, j: usize) -> Option<T> {
+        self.get(i, j).ok()
+    }
+    /// set method for overwriting the element in arr[i][j] position
+    fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError>;
+    /// mutable reference to the element in arr[i][j] position
+    fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError>;
+    /// number of rows of the matrix
+    fn rows(&self) -> usize;
+    /// number of columns of the matrix
+    fn cols(&self) -> usize;
+    /// copy of the i-th row
+    fn row(&self, i: usize) -> Result<Vec<T>, MatrixError>;
+    /// copy of the j-th column
+    fn col(&self, j: usize) -> Result<Vec<T>, MatrixError>;
+    /// every element from left to right and top to bottom whatever the storage order is
+    fn iter_row_major(&self) -> impl Iterator<Item = T> + '_;
+    /// every row from top to bottom, each one copied into its own vec
+    fn iter_rows(&self) -> Rows<'_, T, Self> {
+        Rows {
+            matrix: self,
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+    /// every element in row major order whatever the storage order is, the inverse of
+    /// `MatrixRowMajor::from_flat`
+    fn flatten(&self) -> Vec<T> {
+        self.iter_row_major().collect()
+    }
+    /// the logical rows as nested vecs, the inverse of `new` whatever the storage order is
+    fn to_nested(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.iter_rows().collect()
+    }
+    /// swaps the rows `a` and `b` in place
+    fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), MatrixError>;
+    /// swaps the columns `a` and `b` in place
+    fn swap_cols(&mut self, a: usize, b: usize) -> Result<(), MatrixError>;
+    /// overwrites every element with `value` in place, keeping the storage
+    fn fill(&mut self, value: T);
+    /// runs `f` on every element in place, unlike `map` nothing is allocated
+    fn apply<F: FnMut(&mut T)>(&mut self, f: F);
+
+    /// (rows, cols) of the matrix
+    fn shape(&self) -> (usize, usize) {
+        (self.rows(), self.cols())
+    }
+    /// true when the matrix has as many rows as columns
+    fn is_square(&self) -> bool {
+        self.rows() == self.cols()
+    }
+    /// true when the matrix has no elements at all
+    fn is_empty(&self) -> bool {
+        self.rows() == 0 || self.cols() == 0
+    }
+
+    /// matrix product of `self` and `rhs`, `self.cols()` has to be equal to `rhs.rows()`
+    fn mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default,
+    {
+        check_mul_shape(self, rhs)?;
+        Ok(Self::from_fn(self.rows(), rhs.cols(), |i, j| {
+            (0..self.cols()).fold(T::default(), |acc, k| {
+                acc + self.get(i, k).unwrap() * rhs.get(k, j).unwrap()
+            })
+        }))
+    }
+
+    /// product of the matrix and the column vector `v`, one element per row
+    fn matvec(&self, v: &[T]) -> Result<Vec<T>, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+    {
+        check_vector_len(self, v.len())?;
+        (0..self.rows())
+            .map(|i| {
+                v.iter()
+                    .enumerate()
+                    .try_fold(T::zero(), |acc, (j, &x)| Ok(acc + self.get(i, j)? * x))
+            })
+            .collect()
+    }
+
+    /// matrix product like `mul` but an `Overflow` error instead of a wrapped element
+    fn checked_mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Zero + Checked,
+    {
+        check_mul_shape(self, rhs)?;
+        let mut out = Vec::with_capacity(self.rows() * rhs.cols());
+        for i in 0..self.rows() {
+            for j in 0..rhs.cols() {
+                let mut acc = T::zero();
+                for k in 0..self.cols() {
+                    acc = self
+                        .get(i, k)?
+                        .checked_mul(rhs.get(k, j)?)
+                        .and_then(|x| acc.checked_add(x))
+                        .ok_or_else(|| {
+                            MatrixError::Overflow(format!(
+                                "Overflow: the element ({}, {}) of the product doesn't fit",
+                                i, j
+                            ))
+                        })?;
+                }
+                out.push(acc);
+            }
+        }
+        Ok(Self::from_fn(self.rows(), rhs.cols(), |i, j| {
+            out[i * rhs.cols() + j]
+        }))
+    }
+
+    /// element-wise sum like `add` but every element clamps at the bounds of `T`
+    fn saturating_add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Saturating,
+    {
+        check_same_shape(self, rhs)?;
+        Ok(Self::from_fn(self.rows(), self.cols(), |i, j| {
+            self.get(i, j)
+                .unwrap()
+                .saturating_add(rhs.get(i, j).unwrap())
+        }))
+    }
+
+    /// matrix product like `mul` but every product and partial sum clamps at the bounds of `T`
+    fn saturating_mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Zero + Saturating,
+    {
+        check_mul_shape(self, rhs)?;
+        Ok(Self::from_fn(self.rows(), rhs.cols(), |i, j| {
+            (0..self.cols()).fold(T::zero(), |acc, k| {
+                acc.saturating_add(
+                    self.get(i, k)
+                        .unwrap()
+                        .saturating_mul(rhs.get(k, j).unwrap()),
+                )
+            })
+        }))
+    }
+
+    /// element-wise sum of two matrices with the same shape
+    fn add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>;
+    /// element-wise difference of two matrices with the same shape
+    fn sub(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Sub<Output = T>;
+    /// element-wise product of two matrices with the same shape
+    fn hadamard(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Mul<Output = T>;
+
+    /// multiplies every element by `scalar`
+    fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>;
+
+    /// adds the single row `v` to every row of the matrix
+    fn add_row_vector(&self, v: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        check_broadcast(self, v, true)?;
+        let v = v.row(0)?;
+        Ok(Self::from_fn(self.rows(), self.cols(), |i, j| {
+            self.get(i, j).unwrap() + v[j]
+        }))
+    }
+
+    /// adds the single column `v` to every column of the matrix
+    fn add_col_vector(&self, v: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        check_broadcast(self, v, false)?;
+        let v = v.col(0)?;
+        Ok(Self::from_fn(self.rows(), self.cols(), |i, j| {
+            self.get(i, j).unwrap() + v[i]
+        }))
+    }
+
+    /// the grid `Display` prints, with `precision` decimals for float elements and `pad`
+    /// spaces between the columns
+    fn format_with(&self, precision: usize, pad: usize) -> String
+    where
+        T: Clone + fmt::Display,
+    {
+        let mut out = String::new();
+        fmt_grid(self, &mut out, Some(precision), pad).unwrap();
+        out
+    }
+
+    /// transpose of the matrix in the same layout, this always copies the elements
+    fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols(), self.rows(), |i, j| self.get(j, i).unwrap())
+    }
+
+    /// hermitian transpose, the transpose with every element conjugated, the same as
+    /// `transposed` for real elements
+    fn conjugate_transpose(&self) -> Self
+    where
+        T: Clone + Conjugate,
+    {
+        Self::from_fn(self.cols(), self.rows(), |i, j| {
+            self.get(j, i).unwrap().conj()
+        })
+    }
+
+    /// `n` x `n` matrix with ones on the diagonal and zeros everywhere else
+    fn identity(n: usize) -> Self
+    where
+        T: Zero + One,
+    {
+        Self::from_fn(n, n, |i, j| if i == j { T::one() } else { T::zero() })
+    }
+
+    /// `rows` x `cols` matrix filled with zeros
+    fn zeros(rows: usize, cols: usize) -> Self
+    where
+        T: Zero,
+    {
+        Self::from_fn(rows, cols, |_, _| T::zero())
+    }
+
+    /// `rows` x `cols` matrix filled with ones
+    fn ones(rows: usize, cols: usize) -> Self
+    where
+        T: One,
+    {
+        Self::from_fn(rows, cols, |_, _| T::one())
+    }
+
+    /// sum of the elements on the main diagonal of a square matrix
+    fn trace(&self) -> Result<T, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        check_square(self)?;
+        Ok(self
+            .diagonal()
+            .into_iter()
+            .fold(T::zero(), |acc, x| acc + x))
+    }
+
+    /// dot product of two vectors, each one either a single row or a single column
+    fn dot(&self, rhs: &Self) -> Result<T, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+    {
+        check_vectors(self, rhs)?;
+        Ok(self
+            .iter_row_major()
+            .zip(rhs.iter_row_major())
+            .fold(T::zero(), |acc, (a, b)| acc + a * b))
+    }
+
+    /// euclidean length of the i-th row
+    fn row_norm(&self, i: usize) -> Result<f64, MatrixError>
+    where
+        T: Copy + Into<f64>,
+    {
+        Ok(l2_norm(self.row(i)?))
+    }
+
+    /// euclidean length of the j-th column
+    fn col_norm(&self, j: usize) -> Result<f64, MatrixError>
+    where
+        T: Copy + Into<f64>,
+    {
+        Ok(l2_norm(self.col(j)?))
+    }
+
+    /// square root of the sum of the squares of every element, whatever the storage order is
+    fn frobenius_norm(&self) -> f64
+    where
+        T: Copy + Into<f64>,
+    {
+        l2_norm(self.iter_row_major())
+    }
+
+    /// same shape and every pair of elements no more than `epsilon` apart
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool
+    where
+        T: Copy + Into<f64>,
+    {
+        self.shape() == other.shape()
+            && self
+                .iter_row_major()
+                .zip(other.iter_row_major())
+                .all(|(a, b)| (a.into() - b.into()).abs() <= epsilon)
+    }
+
+    /// sum of every element, zero for an empty matrix
+    fn sum(&self) -> T
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        self.iter_row_major().fold(T::zero(), |acc, x| acc + x)
+    }
+
+    /// sum of every element, `None` when it overflows the element type
+    fn checked_sum(&self) -> Option<T>
+    where
+        T: Copy + Zero + Checked,
+    {
+        self.iter_row_major()
+            .try_fold(T::zero(), |acc, x| acc.checked_add(x))
+    }
+
+    /// smallest element, `None` for an empty matrix
+    fn min(&self) -> Option<T>
+    where
+        T: Clone + PartialOrd,
+    {
+        self.iter_row_major()
+            .reduce(|min, x| if x < min { x } else { min })
+    }
+
+    /// largest element, `None` for an empty matrix
+    fn max(&self) -> Option<T>
+    where
+        T: Clone + PartialOrd,
+    {
+        self.iter_row_major()
+            .reduce(|max, x| if x > max { x } else { max })
+    }
+
+    /// copy with every element limited to `[lo, hi]`
+    fn clamp(&self, lo: T, hi: T) -> Self
+    where
+        T: Copy + PartialOrd,
+    {
+        Self::from_fn(self.rows(), self.cols(), |i, j| {
+            clamp_value(self.get(i, j).unwrap(), lo, hi)
+        })
+    }
+
+    /// limits every element to `[lo, hi]` in place
+    fn clamp_mut(&mut self, lo: T, hi: T)
+    where
+        T: Copy + PartialOrd,
+    {
+        self.apply(|x| *x = clamp_value(*x, lo, hi));
+    }
+
+    /// position of the largest element, the first one in row major order on a tie, `None`
+    /// for an empty matrix
+    fn argmax(&self) -> Option<(usize, usize)>
+    where
+        T: Clone + PartialOrd,
+    {
+        let cols = self.cols();
+        self.iter_row_major()
+            .enumerate()
+            .reduce(|max, x| if x.1 > max.1 { x } else { max })
+            .map(|(k, _)| (k / cols, k % cols))
+    }
+
+    /// position of the smallest element, the first one in row major order on a tie, `None`
+    /// for an empty matrix
+    fn argmin(&self) -> Option<(usize, usize)>
+    where
+        T: Clone + PartialOrd,
+    {
+        let cols = self.cols();
+        self.iter_row_major()
+            .enumerate()
+            .reduce(|min, x| if x.1 < min.1 { x } else { min })
+            .map(|(k, _)| (k / cols, k % cols))
+    }
+
+    /// sum of every row, top to bottom
+    fn row_sums(&self) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        (0..self.rows())
+            .map(|i| (0..self.cols()).fold(T::zero(), |acc, j| acc + self.get(i, j).unwrap()))
+            .collect()
+    }
+
+    /// sum of every column, left to right
+    fn col_sums(&self) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        (0..self.cols())
+            .map(|j| (0..self.rows()).fold(T::zero(), |acc, i| acc + self.get(i, j).unwrap()))
+            .collect()
+    }
+
+    /// true for a square matrix equal to its transpose
+    fn is_symmetric(&self) -> bool
+    where
+        T: Clone + PartialEq,
+    {
+        self.is_square()
+            && (0..self.rows())
+                .all(|i| (0..i).all(|j| self.get(i, j).unwrap() == self.get(j, i).unwrap()))
+    }
+
+    /// like `is_symmetric` but the mirrored elements only have to be `epsilon` apart
+    fn is_symmetric_within(&self, epsilon: f64) -> bool
+    where
+        T: Copy + Into<f64>,
+    {
+        self.is_square()
+            && (0..self.rows()).all(|i| {
+                (0..i).all(|j| {
+                    let a: f64 = self.get(i, j).unwrap().into();
+                    (a - self.get(j, i).unwrap().into()).abs() <= epsilon
+                })
+            })
+    }
+
+    /// copy with every element below the main diagonal zeroed
+    fn upper_triangular(&self) -> Self
+    where
+        T: Clone + Zero,
+    {
+        Self::from_fn(self.rows(), self.cols(), |i, j| {
+            if i > j {
+                T::zero()
+            } else {
+                self.get(i, j).unwrap()
+            }
+        })
+    }
+
+    /// copy with every element above the main diagonal zeroed
+    fn lower_triangular(&self) -> Self
+    where
+        T: Clone + Zero,
+    {
+        Self::from_fn(self.rows(), self.cols(), |i, j| {
+            if i < j {
+                T::zero()
+            } else {
+                self.get(i, j).unwrap()
+            }
+        })
+    }
+
+    /// true when every element below the main diagonal is zero
+    fn is_upper_triangular(&self) -> bool
+    where
+        T: Clone + Zero + PartialEq,
+    {
+        (0..self.rows())
+            .all(|i| (0..i.min(self.cols())).all(|j| self.get(i, j).unwrap() == T::zero()))
+    }
+
+    /// true when every element above the main diagonal is zero
+    fn is_lower_triangular(&self) -> bool
+    where
+        T: Clone + Zero + PartialEq,
+    {
+        (0..self.rows()).all(|i| (i + 1..self.cols()).all(|j| self.get(i, j).unwrap() == T::zero()))
+    }
+
+    /// number of elements that are not zero
+    fn nnz(&self) -> usize
+    where
+        T: Clone + Zero + PartialEq,
+    {
+        self.iter_row_major().filter(|x| *x != T::zero()).count()
+    }
+
+    /// (i, j) of every element that is not zero, in row major order
+    fn nonzero_indices(&self) -> Vec<(usize, usize)>
+    where
+        T: Clone + Zero + PartialEq,
+    {
+        let cols = self.cols();
+        self.iter_row_major()
+            .enumerate()
+            .filter(|(_, x)| *x != T::zero())
+            .map(|(k, _)| (k / cols, k % cols))
+            .collect()
+    }
+
+    /// elements at (k, k) for every k up to the smaller of the two dimensions
+    fn diagonal(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        (0..self.rows().min(self.cols()))
+            .map(|k| self.get(k, k).unwrap())
+            .collect()
+    }
+
+    /// elements at (i, i + k) when `k` is positive or (i - k, i) when it is negative, empty
+    /// once `k` runs past the last row or column
+    fn diagonal_offset(&self, k: isize) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let (row, col) = if k < 0 {
+            (k.unsigned_abs(), 0)
+        } else {
+            (0, k.unsigned_abs())
+        };
+        let len = self
+            .rows()
+            .saturating_sub(row)
+            .min(self.cols().saturating_sub(col));
+        (0..len)
+            .map(|d| self.get(row + d, col + d).unwrap())
+            .collect()
+    }
+
+    /// elements at (k, cols - 1 - k) from the top right corner towards the bottom left for
+    /// every k up to the smaller of the two dimensions
+    fn antidiagonal(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        (0..self.rows().min(self.cols()))
+            .map(|k| self.get(k, self.cols() - 1 - k).unwrap())
+            .collect()
+    }
+
+    /// `self` multiplied by itself `exponent` times using exponentiation by squaring, the
+    /// zeroth power is the identity
+    fn pow(&self, exponent: u32) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default + Zero + One,
+    {
+        check_square(self)?;
+        let mut result = Self::identity(self.rows());
+        let mut base = Self::from_fn(self.rows(), self.cols(), |i, j| self.get(i, j).unwrap());
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// determinant of a square matrix, the product of the diagonal of `U` from `lu` with the
+    /// sign of the row permutation
+    fn determinant(&self) -> Result<T, MatrixError>
+    where
+        T: Float,
+    {
+        let (_, u, perm) = self.lu()?;
+        let det = (0..u.rows()).fold(T::one(), |det, k| det * u.get(k, k).unwrap());
+        Ok(if is_odd_permutation(&perm) { -det } else { det })
+    }
+
+    /// row echelon form of the matrix by forward elimination with partial pivoting, a column
+    /// without a nonzero pivot is skipped
+    fn row_echelon(&self) -> Self
+    where
+        T: Float,
+    {
+        let (rows, cols) = self.shape();
+        let mut a: Vec<T> = self.iter_row_major().collect();
+        forward_eliminate(&mut a, rows, cols);
+        Self::from_fn(rows, cols, |i, j| a[i * cols + j])
+    }
+
+    /// number of nonzero rows left by `row_echelon`, an element counts as zero when it is
+    /// within rounding error of the largest element of the matrix
+    fn rank(&self) -> usize
+    where
+        T: Float,
+    {
+        let (rows, cols) = self.shape();
+        let mut a: Vec<T> = self.iter_row_major().collect();
+        let tolerance = zero_tolerance(&a, rows.max(cols));
+        forward_eliminate(&mut a, rows, cols);
+        (0..rows)
+            .filter(|&i| {
+                a[i * cols..(i + 1) * cols]
+                    .iter()
+                    .any(|x| x.abs() > tolerance)
+            })
+            .count()
+    }
+
+    /// inverse of a square matrix, `lu` once and then the substitutions for every column of
+    /// the identity, and `self * inverse` has to come out close to the identity or the matrix
+    /// is too close to singular for the result to be trusted
+    fn inverse(&self) -> Result<Self, MatrixError>
+    where
+        T: Float,
+    {
+        let (l, u, perm) = self.lu()?;
+        check_nonsingular(self, &u)?;
+        let n = self.rows();
+        let cols: Vec<Vec<T>> = (0..n)
+            .map(|j| {
+                let e: Vec<T> = (0..n)
+                    .map(|i| if i == j { T::one() } else { T::zero() })
+                    .collect();
+                lu_substitute(&l, &u, &perm, &e)
+            })
+            .collect();
+        let size = (0..n).fold(T::zero(), |size, _| size + T::one());
+        let tolerance = T::epsilon().sqrt() * size;
+        for i in 0..n {
+            for (j, col) in cols.iter().enumerate() {
+                let x = (0..n).fold(T::zero(), |x, k| x + self.get(i, k).unwrap() * col[k]);
+                let expected = if i == j { T::one() } else { T::zero() };
+                if (x - expected).abs().partial_cmp(&tolerance) != Some(Ordering::Less) {
+                    return Err(MatrixError::Singular(format!(
+                        "Singular matrix: A * A^-1 is off the identity at ({}, {})",
+                        i, j
+                    )));
+                }
+            }
+        }
+        Ok(Self::from_fn(n, n, |i, j| cols[j][i]))
+    }
+
+    /// solution `x` of the linear system `self * x = b` where `b` is a column with one
+    /// element per row of the square matrix `self`, by `lu` and forward and back substitution
+    fn solve(&self, b: &Self) -> Result<Self, MatrixError>
+    where
+        T: Float,
+    {
+        check_square(self)?;
+        if b.shape() != (self.rows(), 1) {
+            return Err(MatrixError::DimensionMismatch(format!(
+                "Dimension mismatch: a {}x{} system needs a {}x1 right hand side but it is {}x{}",
+                self.rows(),
+                self.cols(),
+                self.rows(),
+                b.rows(),
+                b.cols()
+            )));
+        }
+        let (l, u, perm) = self.lu()?;
+        check_nonsingular(self, &u)?;
+        let x = lu_substitute(&l, &u, &perm, &b.col(0)?);
+        Ok(Self::from_fn(self.rows(), 1, |i, _| x[i]))
+    }
+
+    /// kronecker product, every element of `self` scales a whole copy of `rhs`
+    fn kronecker(&self, rhs: &Self) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        let (p, q) = rhs.shape();
+        Self::from_fn(self.rows() * p, self.cols() * q, |i, j| {
+            self.get(i / p, j / q).unwrap() * rhs.get(i % p, j % q).unwrap()
+        })
+    }
+
+    /// `self` and `rhs` side by side, both need the same number of rows
+    fn hstack(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Clone,
+    {
+        check_stack(self, rhs, true)?;
+        let cols = self.cols();
+        Ok(Self::from_fn(self.rows(), cols + rhs.cols(), |i, j| {
+            if j < cols {
+                self.get(i, j).unwrap()
+            } else {
+                rhs.get(i, j - cols).unwrap()
+            }
+        }))
+    }
+
+    /// `rhs` below `self`, both need the same number of columns
+    fn vstack(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Clone,
+    {
+        check_stack(self, rhs, false)?;
+        let rows = self.rows();
+        Ok(Self::from_fn(rows + rhs.rows(), self.cols(), |i, j| {
+            if i < rows {
+                self.get(i, j).unwrap()
+            } else {
+                rhs.get(i - rows, j).unwrap()
+            }
+        }))
+    }
+
+    /// the same elements in the same row major order laid out as `new_rows` x `new_cols`
+    fn reshape(&self, new_rows: usize, new_cols: usize) -> Result<Self, MatrixError>
+    where
+        T: Clone,
+    {
+        check_reshape(self, new_rows, new_cols)?;
+        let values: Vec<T> = self.iter_row_major().collect();
+        Ok(Self::from_fn(new_rows, new_cols, |i, j| {
+            values[i * new_cols + j].clone()
+        }))
+    }
+
+    /// every row divided by its euclidean length, a row of zeros stays as it is
+    fn normalize_rows(&self) -> Self
+    where
+        T: Float,
+    {
+        let norms: Vec<T> = (0..self.rows())
+            .map(|i| line_norm(self.row(i).unwrap()))
+            .collect();
+        Self::from_fn(self.rows(), self.cols(), |i, j| {
+            let x = self.get(i, j).unwrap();
+            if norms[i] == T::zero() {
+                x
+            } else {
+                x / norms[i]
+            }
+        })
+    }
+
+    /// every column divided by its euclidean length, a column of zeros stays as it is
+    fn normalize_cols(&self) -> Self
+    where
+        T: Float,
+    {
+        let norms: Vec<T> = (0..self.cols())
+            .map(|j| line_norm(self.col(j).unwrap()))
+            .collect();
+        Self::from_fn(self.rows(), self.cols(), |i, j| {
+            let x = self.get(i, j).unwrap();
+            if norms[j] == T::zero() {
+                x
+            } else {
+                x / norms[j]
+            }
+        })
+    }
+
+    /// lower triangular `L` with `L * L^T == self` for a symmetric positive definite matrix,
+    /// a matrix that is not symmetric up to rounding error or has a pivot that is not
+    /// positive, NaN included, is `NotPositiveDefinite`
+    fn cholesky(&self) -> Result<Self, MatrixError>
+    where
+        T: Float,
+    {
+        check_square(self)?;
+        let n = self.rows();
+        let values: Vec<T> = self.iter_row_major().collect();
+        let tolerance = zero_tolerance(&values, n);
+        for i in 0..n {
+            for j in 0..i {
+                let diff = (values[i * n + j] - values[j * n + i]).abs();
+                if diff.partial_cmp(&tolerance) == Some(Ordering::Greater) {
+                    return Err(MatrixError::NotPositiveDefinite(format!(
+                        "Not positive definite: the matrix is not symmetric at ({}, {})",
+                        i, j
+                    )));
+                }
+            }
+        }
+        let mut l = vec![T::zero(); n * n];
+        for j in 0..n {
+            let mut pivot = self.get(j, j)?;
+            for k in 0..j {
+                pivot = pivot - l[j * n + k] * l[j * n + k];
+            }
+            if pivot.partial_cmp(&T::zero()) != Some(Ordering::Greater) {
+                return Err(MatrixError::NotPositiveDefinite(format!(
+                    "Not positive definite: the pivot of column {} is not positive",
+                    j
+                )));
+            }
+            let pivot = pivot.sqrt();
+            l[j * n + j] = pivot;
+            for i in j + 1..n {
+                let mut x = self.get(i, j)?;
+                for k in 0..j {
+                    x = x - l[i * n + k] * l[j * n + k];
+                }
+                l[i * n + j] = x / pivot;
+            }
+        }
+        Ok(Self::from_fn(n, n, |i, j| l[i * n + j]))
+    }
+
+    /// LU decomposition with partial pivoting, `(L, U, perm)` where `L` is unit lower
+    /// triangular, `U` is upper triangular and row `i` of `L * U` is row `perm[i]` of `self`
+    fn lu(&self) -> Result<(Self, Self, Vec<usize>), MatrixError>
+    where
+        T: Float,
+    {
+        check_square(self)?;
+        let n = self.rows();
+        let mut u: Vec<T> = self.iter_row_major().collect();
+        let mut l = vec![T::zero(); n * n];
+        let mut perm: Vec<usize> = (0..n).collect();
+        for k in 0..n {
+            let pivot = pivot_row(&u, n, k, k..n);
+            if pivot != k {
+                for j in 0..n {
+                    u.swap(k * n + j, pivot * n + j);
+                }
+                for j in 0..k {
+                    l.swap(k * n + j, pivot * n + j);
+                }
+                perm.swap(k, pivot);
+            }
+            l[k * n + k] = T::one();
+            // the pivot is the largest so the whole column below it is already zero
+            if u[k * n + k] == T::zero() {
+                continue;
+            }
+            for i in k + 1..n {
+                let factor = u[i * n + k] / u[k * n + k];
+                l[i * n + k] = factor;
+                u[i * n + k] = T::zero();
+                for j in k + 1..n {
+                    u[i * n + j] = u[i * n + j] - factor * u[k * n + j];
+                }
+            }
+        }
+        Ok((
+            Self::from_fn(n, n, |i, j| l[i * n + j]),
+            Self::from_fn(n, n, |i, j| u[i * n + j]),
+            perm,
+        ))
+    }
+
+    /// copy of the rectangular block made of the rows in `row_range` and the columns in
+    /// `col_range`
+    fn submatrix(
+        &self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> Result<Self, MatrixError>
+    where
+        T: Clone,
+    {
+        check_range(&row_range, self.rows())?;
+        check_range(&col_range, self.cols())?;
+        Ok(Self::from_fn(row_range.len(), col_range.len(), |i, j| {
+            self.get(row_range.start + i, col_range.start + j).unwrap()
+        }))
+    }
+
+    /// reshapes the matrix to `new_rows` x `new_cols`, the elements that are still in range
+    /// keep their position, the new cells get `fill` and the rest is dropped
+    fn resize(&mut self, new_rows: usize, new_cols: usize, fill: T)
+    where
+        T: Clone,
+    {
+        let resized = Self::from_fn(new_rows, new_cols, |i, j| {
+            self.try_get(i, j).unwrap_or_else(|| fill.clone())
+        });
+        *self = resized;
+    }
+}
+
+/// additive identity of a numeric type
+pub trait Zero {
+    fn zero() -> Self;
+}
+
+/// multiplicative identity of a numeric type
+pub trait One {
+    fn one() -> Self;
+}
+
+macro_rules! impl_zero_one {
+    ($zero:literal, $one:literal, $($t:ty),*) => {
+        $(
+            impl Zero for $t {
+                fn zero() -> Self {
+                    $zero
+                }
+            }
+
+            impl One for $t {
+                fn one() -> Self {
+                    $one
+                }
+            }
+        )*
+    };
+}
+
+impl_zero_one!(0, 1, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_zero_one!(0.0, 1.0, f32, f64);
+
+/// complex conjugate, real numbers are their own conjugate
+pub trait Conjugate {
+    fn conj(self) -> Self;
+}
+
+macro_rules! impl_conjugate {
+    ($($t:ty),*) => {
+        $(
+            impl Conjugate for $t {
+                fn conj(self) -> Self {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_conjugate!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// floating point element types, needed by the algorithms that divide by pivots
+pub trait Float:
+    Copy
+    + PartialOrd
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn abs(self) -> Self;
+    /// difference between 1 and the next representable number
+    fn epsilon() -> Self;
+    fn sqrt(self) -> Self;
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn epsilon() -> Self {
+                    <$t>::EPSILON
+                }
+
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+/// integer arithmetic that reports overflow instead of wrapping
+pub trait Checked: Sized {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked {
+    ($($t:ty),*) => {
+        $(
+            impl Checked for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// integer arithmetic that clamps at the bounds of the type instead of wrapping
+pub trait Saturating: Sized {
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_saturating {
+    ($($t:ty),*) => {
+        $(
+            impl Saturating for $t {
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$t>::saturating_add(self, rhs)
+                }
+
+                fn saturating_mul(self, rhs: Self) -> Self {
+                    <$t>::saturating_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_saturating!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[derive(Debug)]
+#[doc = "This is the enum for indexing"]
+pub enum MatrixError {
+    /**
+    this means that the given position is out of bounds of the Matrix,
+    for a whole row or column the other coordinate is 0, `offset` is where (i, j) would be
+    in row major order
+    */
+    OutOfBoundIndexing {
+        i: usize,
+        j: usize,
+        offset: usize,
+        rows: usize,
+        cols: usize,
+    },
+    /**
+    this means that the given position is not present in the matrix (aka the len is smaller than either i or j),
+    for a whole row or column the other coordinate is 0
+    */
+    ForbiddenIndexing {
+        i: usize,
+        j: usize,
+        rows: usize,
+        cols: usize,
+    },
+    /**
+    this means that a range of rows or columns is reversed or goes past the `len` of them
+    */
+    InvalidRange {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    /**
+    this means that the block (bi, bj) is not in the `grid_rows` x `grid_cols` grid of blocks
+    of a blocky matrix
+    */
+    BlockIndexing {
+        bi: usize,
+        bj: usize,
+        grid_rows: usize,
+        grid_cols: usize,
+    },
+    /**
+    this means that the rows given to the constructor don't all have the same length
+    */
+    Jagged(String),
+    /**
+    this means that the shapes of the two matrices don't fit the operation
+    */
+    DimensionMismatch(String),
+    /**
+    this means that the operation only makes sense for a square matrix
+    */
+    NotSquare(String),
+    /**
+    this means that a token of the text couldn't be parsed into an element
+    */
+    ParseError(String),
+    /**
+    this means that the matrix has no inverse
+    */
+    Singular(String),
+    /**
+    this means that the matrix is not symmetric positive definite
+    */
+    NotPositiveDefinite(String),
+    /**
+    this means that an integer result doesn't fit in the element type
+    */
+    Overflow(String),
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::OutOfBoundIndexing {
+                i,
+                j,
+                offset,
+                rows,
+                cols,
+            } => write!(
+                f,
+                "Out of bound indexing: (i = {}, j = {}) at offset {} is outside of the {}x{} matrix",
+                i, j, offset, rows, cols
+            ),
+            MatrixError::ForbiddenIndexing { i, j, rows, cols } => write!(
+                f,
+                "Forbidden indexing: (i = {}, j = {}) is outside of the {}x{} matrix",
+                i, j, rows, cols
+            ),
+            MatrixError::InvalidRange { start, end, len } => write!(
+                f,
+                "Invalid range: {}..{} doesn't fit in a len of {}",
+                start, end, len
+            ),
+            MatrixError::BlockIndexing {
+                bi,
+                bj,
+                grid_rows,
+                grid_cols,
+            } => write!(
+                f,
+                "Block indexing: block (bi = {}, bj = {}) is outside of the {}x{} grid of blocks",
+                bi, bj, grid_rows, grid_cols
+            ),
+            MatrixError::Jagged(msg)
+            | MatrixError::DimensionMismatch(msg)
+            | MatrixError::NotSquare(msg)
+            | MatrixError::ParseError(msg)
+            | MatrixError::Singular(msg)
+            | MatrixError::NotPositiveDefinite(msg)
+            | MatrixError::Overflow(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+/// square root of the sum of the squares of the elements
+fn l2_norm<T: Into<f64>>(line: impl IntoIterator<Item = T>) -> f64 {
+    line.into_iter()
+        .map(|x| {
+            let x = x.into();
+            x * x
+        })
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// `x` limited to `[lo, hi]`, `Ord::clamp` would need a total order which floats don't have
+fn clamp_value<T: PartialOrd>(x: T, lo: T, hi: T) -> T {
+    if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }
+}
+
+/// like `l2_norm` but in the element type itself
+fn line_norm<T: Float>(line: Vec<T>) -> T {
+    line.into_iter()
+        .fold(T::zero(), |acc, x| acc + x * x)
+        .sqrt()
+}
+
+/// `x` with `l * u * x` equal to `b` permuted by `perm`, the factors as `lu` returns them and
+/// `u` has to have a nonzero diagonal
+fn lu_substitute<T, M>(l: &M, u: &M, perm: &[usize], b: &[T]) -> Vec<T>
+where
+    T: Float,
+    M: Matrix<T>,
+{
+    let n = perm.len();
+    // forward substitution, `l` has ones on its diagonal
+    let mut y: Vec<T> = Vec::with_capacity(n);
+    for i in 0..n {
+        let sum = (0..i).fold(b[perm[i]], |sum, j| sum - l.get(i, j).unwrap() * y[j]);
+        y.push(sum);
+    }
+    // back substitution from the last row up
+    let mut x = vec![T::zero(); n];
+    for i in (0..n).rev() {
+        let sum = (i + 1..n).fold(y[i], |sum, j| sum - u.get(i, j).unwrap() * x[j]);
+        x[i] = sum / u.get(i, i).unwrap();
+    }
+    x
+}
+
+/// makes sure `a` has no NaN and its `u` factor no pivot within rounding error of zero
+fn check_nonsingular<T, M>(a: &M, u: &M) -> Result<(), MatrixError>
+where
+    T: Float,
+    M: Matrix<T>,
+{
+    let values: Vec<T> = a.iter_row_major().collect();
+    if values.iter().any(|x| x.is_nan()) {
+        return Err(MatrixError::Singular(
+            "Singular matrix: an element is NaN".to_string(),
+        ));
+    }
+    let tolerance = zero_tolerance(&values, a.rows());
+    // a NaN pivot compares as neither bigger nor smaller and has to count as zero too
+    if (0..u.rows())
+        .any(|k| u.get(k, k).unwrap().abs().partial_cmp(&tolerance) != Some(Ordering::Greater))
+    {
+        return Err(MatrixError::Singular(
+            "Singular matrix: a pivot of the elimination is zero".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// true when `perm` takes an odd number of swaps to sort, a cycle of length `len` takes
+/// `len - 1` of them
+fn is_odd_permutation(perm: &[usize]) -> bool {
+    let mut seen = vec![false; perm.len()];
+    let mut swaps = 0;
+    for start in 0..perm.len() {
+        if seen[start] {
+            continue;
+        }
+        let mut i = start;
+        while !seen[i] {
+            seen[i] = true;
+            i = perm[i];
+            swaps += 1;
+        }
+        swaps -= 1;
+    }
+    swaps % 2 == 1
+}
+
+/// how close to zero a value computed by elimination on `a` can be and still count as
+/// zero, the rounding error grows with the largest element and the size `n` of the matrix
+fn zero_tolerance<T: Float>(a: &[T], n: usize) -> T {
+    let largest = a
+        .iter()
+        .map(|x| x.abs())
+        .fold(T::zero(), |m, x| if x > m { x } else { m });
+    let size = (0..n).fold(T::zero(), |size, _| size + T::one());
+    T::epsilon() * size * largest
+}
+
+/// row in `candidates` with the largest element in column `k` of the flat row major `a`
+fn pivot_row<T: Float>(a: &[T], cols: usize, k: usize, candidates: Range<usize>) -> usize {
+    candidates
+        .max_by(|&x, &y| {
+            a[x * cols + k]
+                .abs()
+                .partial_cmp(&a[y * cols + k].abs())
+                .unwrap_or(Ordering::Equal)
+        })
+        .unwrap()
+}
+
+/// forward elimination with partial pivoting on a flat row major `rows` x `cols` copy of a
+/// matrix, leaves it in row echelon form for `row_echelon` and `rank`, which unlike `lu`
+/// also take rectangular matrices
+fn forward_eliminate<T: Float>(a: &mut [T], rows: usize, cols: usize) {
+    let mut r = 0;
+    for k in 0..cols {
+        if r == rows {
+            break;
+        }
+        let pivot = pivot_row(a, cols, k, r..rows);
+        if a[pivot * cols + k] == T::zero() {
+            continue;
+        }
+        if pivot != r {
+            for j in 0..cols {
+                a.swap(r * cols + j, pivot * cols + j);
+            }
+        }
+        for i in r + 1..rows {
+            let factor = a[i * cols + k] / a[r * cols + k];
+            a[i * cols + k] = T::zero();
+            for j in k + 1..cols {
+                a[i * cols + j] = a[i * cols + j] - factor * a[r * cols + j];
+            }
+        }
+        r += 1;
+    }
+}
+
+/// makes sure every row of the slice has as many elements as the first one
+fn check_rectangular<T>(slice: &[Vec<T>]) -> Result<(), MatrixError> {
+    let Some(first) = slice.first() else {
+        return Ok(());
+    };
+    match slice.iter().position(|row| row.len() != first.len()) {
+        Some(i) => Err(MatrixError::Jagged(format!(
+            "Jagged rows: row 0 has {} elements but row {} has {}",
+            first.len(),
+            i,
+            slice[i].len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// makes sure `lhs * rhs` is defined
+fn check_mul_shape<T, L: Matrix<T>, R: Matrix<T>>(lhs: &L, rhs: &R) -> Result<(), MatrixError> {
+    if lhs.cols() != rhs.rows() {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: cannot multiply a {}x{} matrix by a {}x{} matrix",
+            lhs.rows(),
+            lhs.cols(),
+            rhs.rows(),
+            rhs.cols()
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure both matrices have the same shape for an element-wise operation
+fn check_same_shape<T, L: Matrix<T>, R: Matrix<T>>(lhs: &L, rhs: &R) -> Result<(), MatrixError> {
+    if lhs.rows() != rhs.rows() || lhs.cols() != rhs.cols() {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: a {}x{} matrix and a {}x{} matrix don't have the same shape",
+            lhs.rows(),
+            lhs.cols(),
+            rhs.rows(),
+            rhs.cols()
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure both matrices are vectors (a single row or a single column) of the same length
+fn check_vectors<T, L: Matrix<T>, R: Matrix<T>>(lhs: &L, rhs: &R) -> Result<(), MatrixError> {
+    let is_vector = |rows: usize, cols: usize| rows == 1 || cols == 1;
+    if !is_vector(lhs.rows(), lhs.cols())
+        || !is_vector(rhs.rows(), rhs.cols())
+        || lhs.rows() * lhs.cols() != rhs.rows() * rhs.cols()
+    {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: a {}x{} matrix and a {}x{} matrix are not vectors of the same length",
+            lhs.rows(),
+            lhs.cols(),
+            rhs.rows(),
+            rhs.cols()
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure the matrices can be put side by side (`horizontal`) or one below the other
+fn check_stack<T, L: Matrix<T>, R: Matrix<T>>(
+    lhs: &L,
+    rhs: &R,
+    horizontal: bool,
+) -> Result<(), MatrixError> {
+    let (fits, side) = if horizontal {
+        (lhs.rows() == rhs.rows(), "next to")
+    } else {
+        (lhs.cols() == rhs.cols(), "below")
+    };
+    if !fits {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: cannot put a {}x{} matrix {} a {}x{} matrix",
+            rhs.rows(),
+            rhs.cols(),
+            side,
+            lhs.rows(),
+            lhs.cols()
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure the matrix has exactly `rows * cols` elements
+fn check_reshape<T, M: Matrix<T>>(matrix: &M, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    if matrix.rows() * matrix.cols() != rows * cols {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: cannot reshape a {}x{} matrix into {}x{}",
+            matrix.rows(),
+            matrix.cols(),
+            rows,
+            cols
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure a flat buffer of `len` elements fills a `rows` x `cols` matrix exactly
+fn check_flat_len(len: usize, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    if len != rows * cols {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: {} elements don't fill a {}x{} matrix",
+            len, rows, cols
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure `v` is a single row as long as a row of `matrix` (`row`) or a single column
+/// as long as one of its columns
+fn check_broadcast<T, M: Matrix<T>, V: Matrix<T>>(
+    matrix: &M,
+    v: &V,
+    row: bool,
+) -> Result<(), MatrixError> {
+    let expected = if row {
+        (1, matrix.cols())
+    } else {
+        (matrix.rows(), 1)
+    };
+    if v.shape() != expected {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: a {}x{} matrix takes a {}x{} vector but it is {}x{}",
+            matrix.rows(),
+            matrix.cols(),
+            expected.0,
+            expected.1,
+            v.rows(),
+            v.cols()
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure a vector of `len` elements can multiply the matrix from the right
+fn check_vector_len<T, M: Matrix<T>>(matrix: &M, len: usize) -> Result<(), MatrixError> {
+    if matrix.cols() != len {
+        return Err(MatrixError::DimensionMismatch(format!(
+            "Dimension mismatch: cannot multiply a {}x{} matrix by a vector of {} elements",
+            matrix.rows(),
+            matrix.cols(),
+            len
+        )));
+    }
+    Ok(())
+}
+
+/// makes sure the matrix is square
+fn check_square<T, M: Matrix<T>>(matrix: &M) -> Result<(), MatrixError> {
+    if !matrix.is_square() {
+        return Err(MatrixError::NotSquare(format!(
+            "Not square: the matrix is {}x{}",
+            matrix.rows(),
+            matrix.cols()
+        )));
+    }
+    Ok(())
+}
+
+/// error for the position (i, j) that is not in a `rows` x `cols` matrix, an empty matrix
+/// has no position at all so every index into it is simply out of bounds
+fn index_error(forbidden: bool, i: usize, j: usize, rows: usize, cols: usize) -> MatrixError {
+    if forbidden && rows > 0 && cols > 0 {
+        MatrixError::ForbiddenIndexing { i, j, rows, cols }
+    } else {
+        MatrixError::OutOfBoundIndexing {
+            i,
+            j,
+            // saturating since an index far past the end would overflow the product
+            offset: i.saturating_mul(cols).saturating_add(j),
+            rows,
+            cols,
+        }
+    }
+}
+
+/// checks (i, j) against a `rows` x `cols` matrix the way the row major layout does, a column
+/// past the end is forbidden and a row past the end is out of bounds
+fn check_index(i: usize, j: usize, rows: usize, cols: usize) -> Result<(), MatrixError> {
+    if j >= cols {
+        return Err(index_error(true, i, j, rows, cols));
+    }
+    if i >= rows {
+        return Err(index_error(false, i, j, rows, cols));
+    }
+    Ok(())
+}
+
+/// swaps two contiguous lines of `len` elements starting at `a * len` and `b * len`
+fn swap_contiguous<T>(arr: &mut [T], len: usize, a: usize, b: usize) {
+    if a == b {
+        return;
+    }
+    let (lo, hi) = (a.min(b), a.max(b));
+    let (head, tail) = arr.split_at_mut(hi * len);
+    head[lo * len..(lo + 1) * len].swap_with_slice(&mut tail[..len]);
+}
+
+/// swaps two lines that are interleaved with a step of `stride` in the flat storage
+fn swap_strided<T>(arr: &mut [T], stride: usize, a: usize, b: usize) {
+    if a == b {
+        return;
+    }
+    for k in (0..arr.len()).step_by(stride) {
+        arr.swap(k + a, k + b);
+    }
+}
+
+/// makes sure `range` is a valid range of rows or columns of a matrix with `len` of them
+fn check_range(range: &Range<usize>, len: usize) -> Result<(), MatrixError> {
+    if range.start > range.end || range.end > len {
+        return Err(MatrixError::InvalidRange {
+            start: range.start,
+            end: range.end,
+            len,
+        });
+    }
+    Ok(())
+}
+
+/// writes the matrix one row per line with every column right aligned to the widest element
+/// and `pad` spaces between the columns, `precision` sets the decimals of float elements
+fn fmt_grid<T, M, W>(matrix: &M, f: &mut W, precision: Option<usize>, pad: usize) -> fmt::Result
+where
+    T: Clone + fmt::Display,
+    M: Matrix<T>,
+    W: fmt::Write,
+{
+    let cells: Vec<String> = matrix
+        .iter_row_major()
+        .map(|value| match precision {
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        })
+        .collect();
+    let width = cells
+        .iter()
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (i, row) in cells.chunks(matrix.cols().max(1)).enumerate() {
+        if i > 0 {
+            writeln!(f)?;
+        }
+        for (j, cell) in row.iter().enumerate() {
+            if j > 0 {
+                write!(f, "{:pad$}", "", pad = pad)?;
+            }
+            write!(f, "{:>width$}", cell, width = width)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixRowMajor<T> {
+    arr: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+impl<T> Matrix<T> for MatrixRowMajor<T>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        Self {
+            arr: Vec::new(),
+            rows: 0,
+            cols: 0,
+        }
+    }
+
+    fn new(slice: &[Vec<T>]) -> Self {
+        Self::try_new(slice).unwrap()
+    }
+
+    fn try_new(slice: &[Vec<T>]) -> Result<Self, MatrixError> {
+        check_rectangular(slice)?;
+        if slice.is_empty() {
+            return Ok(Self::default());
+        }
+        let (rows, cols) = (slice.len(), slice[0].len());
+        // one allocation up front instead of letting `collect` grow the vec
+        let mut arr = Vec::with_capacity(rows * cols);
+        for inner in slice {
+            arr.extend_from_slice(inner);
+        }
+        Ok(Self { arr, rows, cols })
+    }
+
+    fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let mut arr = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                arr.push(f(i, j));
+            }
+        }
+        Self { arr, rows, cols }
+    }
+
+    fn get(&self, i: usize, j: usize) -> Result<T, MatrixError> {
+        Ok(self.arr[self.offset(i, j)?].clone())
+    }
+
+    fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        let offset = self.offset(i, j)?;
+        self.arr[offset] = value;
+        Ok(())
+    }
+
+    fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError> {
+        let offset = self.offset(i, j)?;
+        Ok(&mut self.arr[offset])
+    }
+
+    /// walks both operands in storage order (i, k, j) so the inner loop only touches contiguous rows
+    fn mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default,
+    {
+        check_mul_shape(self, rhs)?;
+        let mut arr = vec![T::default(); self.rows * rhs.cols];
+        for (out_row, lhs_row) in arr
+            .chunks_mut(rhs.cols.max(1))
+            .zip(self.arr.chunks(self.cols.max(1)))
+        {
+            Self::mul_row_into(out_row, lhs_row, rhs);
+        }
+        Ok(Self {
+            arr,
+            rows: self.rows,
+            cols: rhs.cols,
+        })
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Sub<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+
+    fn hadamard(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a * b)
+    }
+
+    fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        Self {
+            arr: self.arr.iter().map(|&x| x * scalar).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    fn row(&self, i: usize) -> Result<Vec<T>, MatrixError> {
+        if i >= self.rows {
+            return Err(index_error(false, i, 0, self.rows, self.cols));
+        }
+        Ok(self.row_slice(i).to_vec())
+    }
+
+    fn col(&self, j: usize) -> Result<Vec<T>, MatrixError> {
+        if j >= self.cols {
+            return Err(index_error(true, 0, j, self.rows, self.cols));
+        }
+        Ok(self
+            .arr
+            .iter()
+            .skip(j)
+            .step_by(self.cols)
+            .cloned()
+            .collect())
+    }
+
+    fn iter_row_major(&self) -> impl Iterator<Item = T> + '_ {
+        self.arr.iter().cloned()
+    }
+
+    /// the storage already is in row major order so it is cloned as a whole
+    fn flatten(&self) -> Vec<T> {
+        self.arr.clone()
+    }
+
+    /// copies whole slices of each row instead of going element by element
+    fn submatrix(
+        &self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> Result<Self, MatrixError>
+    where
+        T: Clone,
+    {
+        check_range(&row_range, self.rows)?;
+        check_range(&col_range, self.cols)?;
+        let mut arr = Vec::with_capacity(row_range.len() * col_range.len());
+        for i in row_range.clone() {
+            arr.extend_from_slice(&self.row_slice(i)[col_range.clone()]);
+        }
+        Ok(Self {
+            arr,
+            rows: row_range.len(),
+            cols: col_range.len(),
+        })
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for i in [a, b] {
+            if i >= self.rows {
+                return Err(index_error(false, i, 0, self.rows, self.cols));
+            }
+        }
+        swap_contiguous(&mut self.arr, self.cols, a, b);
+        Ok(())
+    }
+
+    fn swap_cols(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for j in [a, b] {
+            if j >= self.cols {
+                return Err(index_error(true, 0, j, self.rows, self.cols));
+            }
+        }
+        swap_strided(&mut self.arr, self.cols, a, b);
+        Ok(())
+    }
+
+    fn fill(&mut self, value: T) {
+        self.arr.fill(value);
+    }
+
+    /// only has to move elements around when the length of a row changes, otherwise the
+    /// flat storage just grows or shrinks at the end
+    fn resize(&mut self, new_rows: usize, new_cols: usize, fill: T) {
+        if new_cols == self.cols {
+            self.arr.resize(new_rows * new_cols, fill);
+            self.rows = new_rows;
+            return;
+        }
+        *self = Self::from_fn(new_rows, new_cols, |i, j| {
+            self.try_get(i, j).unwrap_or_else(|| fill.clone())
+        });
+    }
+
+    /// copies whole rows of both matrices one after the other
+    fn hstack(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        check_stack(self, rhs, true)?;
+        let mut arr = Vec::with_capacity(self.arr.len() + rhs.arr.len());
+        for i in 0..self.rows {
+            arr.extend_from_slice(self.row_slice(i));
+            arr.extend_from_slice(rhs.row_slice(i));
+        }
+        Ok(Self {
+            arr,
+            rows: self.rows,
+            cols: self.cols + rhs.cols,
+        })
+    }
+
+    /// the rows of `rhs` just go after the rows of `self` in the flat storage
+    fn vstack(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        check_stack(self, rhs, false)?;
+        let mut arr = Vec::with_capacity(self.arr.len() + rhs.arr.len());
+        arr.extend_from_slice(&self.arr);
+        arr.extend_from_slice(&rhs.arr);
+        Ok(Self {
+            arr,
+            rows: self.rows + rhs.rows,
+            cols: self.cols,
+        })
+    }
+
+    /// the flat storage is already in row major order so it is kept as it is
+    fn reshape(&self, new_rows: usize, new_cols: usize) -> Result<Self, MatrixError> {
+        check_reshape(self, new_rows, new_cols)?;
+        Ok(Self {
+            arr: self.arr.clone(),
+            rows: new_rows,
+            cols: new_cols,
+        })
+    }
+
+    fn apply<F: FnMut(&mut T)>(&mut self, f: F) {
+        self.arr.iter_mut().for_each(f);
+    }
+
+    /// appends every row to the flat storage as it comes, without keeping the rows around
+    fn from_rows<I: IntoIterator<Item = Vec<T>>>(iter: I) -> Result<Self, MatrixError> {
+        let mut iter = iter.into_iter();
+        let Some(first) = iter.next() else {
+            return Ok(Self::default());
+        };
+        let cols = first.len();
+        let mut arr = first;
+        arr.reserve(iter.size_hint().0 * cols);
+        let mut rows = 1;
+        for row in iter {
+            if row.len() != cols {
+                return Err(MatrixError::Jagged(format!(
+                    "Jagged rows: row 0 has {} elements but row {} has {}",
+                    cols,
+                    rows,
+                    row.len()
+                )));
+            }
+            arr.extend(row);
+            rows += 1;
+        }
+        Ok(Self { arr, rows, cols })
+    }
+
+    fn row_sums(&self) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        (0..self.rows)
+            .map(|i| self.row_slice(i).iter().fold(T::zero(), |acc, &x| acc + x))
+            .collect()
+    }
+
+    /// adds whole rows into the running sums instead of striding down every column
+    fn col_sums(&self) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        let mut sums = vec![T::zero(); self.cols];
+        for i in 0..self.rows {
+            for (sum, &x) in sums.iter_mut().zip(self.row_slice(i)) {
+                *sum = *sum + x;
+            }
+        }
+        sums
+    }
+
+    /// one dot product per contiguous row
+    fn matvec(&self, v: &[T]) -> Result<Vec<T>, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+    {
+        check_vector_len(self, v.len())?;
+        Ok((0..self.rows)
+            .map(|i| {
+                self.row_slice(i)
+                    .iter()
+                    .zip(v)
+                    .fold(T::zero(), |acc, (&a, &x)| acc + a * x)
+            })
+            .collect())
+    }
+}
+
+impl<T> MatrixRowMajor<T> {
+    /// position of arr[i][j] in the flat storage, checked against the bounds of the matrix
+    fn offset(&self, i: usize, j: usize) -> Result<usize, MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        Ok(i * self.cols + j)
+    }
+
+    /// combines the two matrices element by element, both have the same layout so the flat
+    /// storages line up
+    fn zip_with<F>(&self, rhs: &Self, f: F) -> Result<Self, MatrixError>
+    where
+        T: Copy,
+        F: Fn(T, T) -> T,
+    {
+        check_same_shape(self, rhs)?;
+        Ok(Self {
+            arr: self
+                .arr
+                .iter()
+                .zip(&rhs.arr)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+            rows: self.rows,
+            cols: self.cols,
+        })
+    }
+
+    /// combines `rhs` into `self` element by element without allocating
+    fn zip_assign<F>(&mut self, rhs: &Self, f: F) -> Result<(), MatrixError>
+    where
+        T: Copy,
+        F: Fn(&mut T, T),
+    {
+        check_same_shape(self, rhs)?;
+        for (a, &b) in self.arr.iter_mut().zip(&rhs.arr) {
+            f(a, b);
+        }
+        Ok(())
+    }
+
+    /// the transpose of a row-major matrix is the same flat storage read as column-major,
+    /// so the elements are copied over as they are without being reordered
+    pub fn transpose(&self) -> MatrixColMajor<T>
+    where
+        T: Clone,
+    {
+        MatrixColMajor {
+            arr: self.arr.clone(),
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// same as `transpose` but reuses the storage instead of copying it
+    pub fn into_transpose(self) -> MatrixColMajor<T> {
+        MatrixColMajor {
+            arr: self.arr,
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// the i-th row as it sits in the flat storage, `i` has to be in range
+    fn row_slice(&self, i: usize) -> &[T] {
+        &self.arr[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// element in arr[i][j] position without any bounds checking, meant for hot loops where
+    /// the indices are already known to be valid
+    ///
+    /// # Safety
+    ///
+    /// `i` has to be smaller than `rows()` and `j` smaller than `cols()`
+    pub unsafe fn get_unchecked(&self, i: usize, j: usize) -> T
+    where
+        T: Clone,
+    {
+        // SAFETY: the caller guarantees the position is inside the matrix
+        unsafe { self.arr.get_unchecked(i * self.cols + j).clone() }
+    }
+
+    /// new matrix of the same shape and layout with `f` applied to every element
+    pub fn map<U>(&self, f: impl FnMut(T) -> U) -> MatrixRowMajor<U>
+    where
+        T: Clone,
+    {
+        MatrixRowMajor {
+            arr: self.arr.iter().cloned().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// accumulates one row of `lhs * rhs` into `out_row`, `lhs_row` being the matching row of
+    /// the left operand
+    fn mul_row_into(out_row: &mut [T], lhs_row: &[T], rhs: &Self)
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T>,
+    {
+        for (&a, rhs_row) in lhs_row.iter().zip(rhs.arr.chunks(rhs.cols.max(1))) {
+            for (out, &b) in out_row.iter_mut().zip(rhs_row) {
+                *out = *out + a * b;
+            }
+        }
+    }
+
+    /// same as `mul` but the rows of the result are split between one thread per available
+    /// core, every row is computed exactly like the serial path does it. only built with the
+    /// `parallel` feature, the threads are std scoped threads and not a rayon pool
+    #[cfg(feature = "parallel")]
+    pub fn par_mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default + Send + Sync,
+    {
+        check_mul_shape(self, rhs)?;
+        let mut arr = vec![T::default(); self.rows * rhs.cols];
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let rows_per_thread = self.rows.div_ceil(threads).max(1);
+        let (lhs_cols, rhs_cols) = (self.cols.max(1), rhs.cols.max(1));
+        thread::scope(|scope| {
+            for (out, lhs) in arr
+                .chunks_mut(rows_per_thread * rhs_cols)
+                .zip(self.arr.chunks(rows_per_thread * lhs_cols))
+            {
+                scope.spawn(move || {
+                    for (out_row, lhs_row) in out.chunks_mut(rhs_cols).zip(lhs.chunks(lhs_cols)) {
+                        Self::mul_row_into(out_row, lhs_row, rhs);
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            arr,
+            rows: self.rows,
+            cols: rhs.cols,
+        })
+    }
+
+    /// takes `data` as the storage of a `rows` x `cols` matrix, row by row, without copying it
+    pub fn from_flat(data: Vec<T>, rows: usize, cols: usize) -> Result<Self, MatrixError> {
+        check_flat_len(data.len(), rows, cols)?;
+        Ok(Self {
+            arr: data,
+            rows,
+            cols,
+        })
+    }
+
+    /// the flat storage, row after row, so arr[i][j] is at `i * cols() + j`
+    pub fn as_slice(&self) -> &[T] {
+        &self.arr
+    }
+
+    /// borrowed window onto the rows `row_range` and columns `col_range`, unlike `submatrix`
+    /// nothing is copied
+    pub fn view(
+        &self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> Result<MatrixView<'_, T>, MatrixError> {
+        check_range(&row_range, self.rows)?;
+        check_range(&col_range, self.cols)?;
+        Ok(MatrixView {
+            window: Window::new(row_range, col_range, self.cols, 1),
+            arr: &self.arr,
+        })
+    }
+
+    /// writable window onto the rows `row_range` and columns `col_range`, the writes go
+    /// straight into this matrix
+    pub fn view_mut(
+        &mut self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> Result<MatrixViewMut<'_, T>, MatrixError> {
+        check_range(&row_range, self.rows)?;
+        check_range(&col_range, self.cols)?;
+        Ok(MatrixViewMut {
+            window: Window::new(row_range, col_range, self.cols, 1),
+            arr: &mut self.arr,
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for MatrixRowMajor<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        match self.offset(i, j) {
+            Ok(offset) => &self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for MatrixRowMajor<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        match self.offset(i, j) {
+            Ok(offset) => &mut self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> fmt::Display for MatrixRowMajor<T>
+where
+    T: Clone + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_grid(self, f, None, 1)
+    }
+}
+
+/// parses a grid with one row per line and the elements of a row separated by whitespace,
+/// blank lines are skipped
+impl<T> FromStr for MatrixRowMajor<T>
+where
+    T: Clone + FromStr,
+{
+    type Err = MatrixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                line.split_whitespace()
+                    .enumerate()
+                    .map(|(j, token)| {
+                        token.parse().map_err(|_| {
+                            MatrixError::ParseError(format!(
+                                "Parse error: cannot parse {:?} at row {} column {}",
+                                token, i, j
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<T>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::try_new(&rows)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl<T> MatrixRowMajor<T>
+where
+    T: Clone + FromStr,
+{
+    /// reads comma separated rows, one record per line, a field can be quoted to hold commas,
+    /// line breaks or `""` for a quote, and the spaces around an unquoted cell are ignored
+    pub fn from_csv_reader<R: std::io::Read>(mut r: R) -> Result<Self, MatrixError> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)
+            .map_err(|err| MatrixError::ParseError(format!("Parse error: {}", err)))?;
+        let rows = csv_records(&text)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, record)| {
+                record
+                    .iter()
+                    .enumerate()
+                    .map(|(j, field)| {
+                        field.parse().map_err(|_| {
+                            MatrixError::ParseError(format!(
+                                "Parse error: cannot parse {:?} at row {} column {}",
+                                field, i, j
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<T>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::try_new(&rows)
+    }
+}
+
+/// splits csv text into records of unquoted fields, blank lines are skipped, an unquoted field
+/// is trimmed and a quoted one is kept as it is between the quotes
+#[cfg(feature = "csv")]
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, MatrixError> {
+    let finish = |field: &mut String, was_quoted: bool| {
+        let field = std::mem::take(field);
+        if was_quoted {
+            field
+        } else {
+            field.trim().to_string()
+        }
+    };
+    let mut records = Vec::new();
+    let (mut record, mut field) = (Vec::new(), String::new());
+    let (mut quoted, mut was_quoted) = (false, false);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.trim().is_empty() && !was_quoted => {
+                field.clear();
+                quoted = true;
+                was_quoted = true;
+            }
+            ',' if !quoted => {
+                record.push(finish(&mut field, was_quoted));
+                was_quoted = false;
+            }
+            '\n' if !quoted => {
+                if field.ends_with('\r') {
+                    field.pop();
+                }
+                if !record.is_empty() || was_quoted || !field.trim().is_empty() {
+                    record.push(finish(&mut field, was_quoted));
+                    records.push(std::mem::take(&mut record));
+                }
+                field.clear();
+                was_quoted = false;
+            }
+            // only the line break of a crlf may sit between a closing quote and the delimiter
+            '\r' if was_quoted && !quoted && matches!(chars.peek(), Some('\n') | None) => {}
+            _ if was_quoted && !quoted => {
+                return Err(MatrixError::ParseError(format!(
+                    "Parse error: unexpected {:?} after a closing quote in row {}",
+                    c,
+                    records.len()
+                )));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(MatrixError::ParseError(format!(
+            "Parse error: unterminated quote in row {}",
+            records.len()
+        )));
+    }
+    if !record.is_empty() || was_quoted || !field.trim().is_empty() {
+        record.push(finish(&mut field, was_quoted));
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[derive(Clone)]
+pub struct MatrixColMajor<T> {
+    arr: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+impl<T> Matrix<T> for MatrixColMajor<T>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        Self {
+            arr: Vec::new(),
+            rows: 0,
+            cols: 0,
+        }
+    }
+
+    fn new(slice: &[Vec<T>]) -> Self {
+        Self::try_new(slice).unwrap()
+    }
+
+    fn try_new(slice: &[Vec<T>]) -> Result<Self, MatrixError> {
+        check_rectangular(slice)?;
+        if slice.is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self::from_fn(slice.len(), slice[0].len(), |i, j| {
+            slice[i][j].clone()
+        }))
+    }
+
+    fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let mut arr = Vec::with_capacity(rows * cols);
+        for j in 0..cols {
+            for i in 0..rows {
+                arr.push(f(i, j));
+            }
+        }
+        Self { arr, rows, cols }
+    }
+
+    fn get(&self, i: usize, j: usize) -> Result<T, MatrixError> {
+        Ok(self.arr[self.offset(i, j)?].clone())
+    }
+
+    fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        let offset = self.offset(i, j)?;
+        self.arr[offset] = value;
+        Ok(())
+    }
+
+    fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError> {
+        let offset = self.offset(i, j)?;
+        Ok(&mut self.arr[offset])
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Sub<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+
+    fn hadamard(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a * b)
+    }
+
+    fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        Self {
+            arr: self.arr.iter().map(|&x| x * scalar).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    fn row(&self, i: usize) -> Result<Vec<T>, MatrixError> {
+        if i >= self.rows {
+            return Err(index_error(true, i, 0, self.rows, self.cols));
+        }
+        Ok(self
+            .arr
+            .iter()
+            .skip(i)
+            .step_by(self.rows)
+            .cloned()
+            .collect())
+    }
+
+    fn col(&self, j: usize) -> Result<Vec<T>, MatrixError> {
+        if j >= self.cols {
+            return Err(index_error(false, 0, j, self.rows, self.cols));
+        }
+        Ok(self.arr[j * self.rows..(j + 1) * self.rows].to_vec())
+    }
+
+    fn iter_row_major(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.rows).flat_map(move |i| self.arr.iter().skip(i).step_by(self.rows).cloned())
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for i in [a, b] {
+            if i >= self.rows {
+                return Err(index_error(true, i, 0, self.rows, self.cols));
+            }
+        }
+        swap_strided(&mut self.arr, self.rows, a, b);
+        Ok(())
+    }
+
+    fn swap_cols(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for j in [a, b] {
+            if j >= self.cols {
+                return Err(index_error(false, 0, j, self.rows, self.cols));
+            }
+        }
+        swap_contiguous(&mut self.arr, self.rows, a, b);
+        Ok(())
+    }
+
+    fn fill(&mut self, value: T) {
+        self.arr.fill(value);
+    }
+
+    /// only has to move elements around when the length of a column changes, otherwise the
+    /// flat storage just grows or shrinks at the end
+    fn resize(&mut self, new_rows: usize, new_cols: usize, fill: T) {
+        if new_rows == self.rows {
+            self.arr.resize(new_rows * new_cols, fill);
+            self.cols = new_cols;
+            return;
+        }
+        *self = Self::from_fn(new_rows, new_cols, |i, j| {
+            self.try_get(i, j).unwrap_or_else(|| fill.clone())
+        });
+    }
+
+    /// the columns of `rhs` just go after the columns of `self` in the flat storage
+    fn hstack(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        check_stack(self, rhs, true)?;
+        let mut arr = Vec::with_capacity(self.arr.len() + rhs.arr.len());
+        arr.extend_from_slice(&self.arr);
+        arr.extend_from_slice(&rhs.arr);
+        Ok(Self {
+            arr,
+            rows: self.rows,
+            cols: self.cols + rhs.cols,
+        })
+    }
+
+    /// copies whole columns of both matrices one after the other
+    fn vstack(&self, rhs: &Self) -> Result<Self, MatrixError> {
+        check_stack(self, rhs, false)?;
+        let mut arr = Vec::with_capacity(self.arr.len() + rhs.arr.len());
+        for j in 0..self.cols {
+            arr.extend_from_slice(&self.arr[j * self.rows..(j + 1) * self.rows]);
+            arr.extend_from_slice(&rhs.arr[j * rhs.rows..(j + 1) * rhs.rows]);
+        }
+        Ok(Self {
+            arr,
+            rows: self.rows + rhs.rows,
+            cols: self.cols,
+        })
+    }
+
+    fn apply<F: FnMut(&mut T)>(&mut self, f: F) {
+        self.arr.iter_mut().for_each(f);
+    }
+
+    /// adds whole columns into the running sums instead of striding along every row
+    fn row_sums(&self) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        let mut sums = vec![T::zero(); self.rows];
+        for j in 0..self.cols {
+            for (sum, &x) in sums
+                .iter_mut()
+                .zip(&self.arr[j * self.rows..(j + 1) * self.rows])
+            {
+                *sum = *sum + x;
+            }
+        }
+        sums
+    }
+
+    fn col_sums(&self) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Zero,
+    {
+        (0..self.cols)
+            .map(|j| {
+                self.arr[j * self.rows..(j + 1) * self.rows]
+                    .iter()
+                    .fold(T::zero(), |acc, &x| acc + x)
+            })
+            .collect()
+    }
+
+    /// adds every contiguous column scaled by its element of `v` into the result
+    fn matvec(&self, v: &[T]) -> Result<Vec<T>, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+    {
+        check_vector_len(self, v.len())?;
+        let mut out = vec![T::zero(); self.rows];
+        for (j, &x) in v.iter().enumerate() {
+            for (acc, &a) in out
+                .iter_mut()
+                .zip(&self.arr[j * self.rows..(j + 1) * self.rows])
+            {
+                *acc = *acc + a * x;
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl<T> MatrixColMajor<T> {
+    /// position of arr[i][j] in the flat storage, checked against the bounds of the matrix
+    fn offset(&self, i: usize, j: usize) -> Result<usize, MatrixError> {
+        if i >= self.rows {
+            return Err(index_error(true, i, j, self.rows, self.cols));
+        }
+        if j >= self.cols {
+            return Err(index_error(false, i, j, self.rows, self.cols));
+        }
+        Ok(j * self.rows + i)
+    }
+
+    /// combines the two matrices element by element, both have the same layout so the flat
+    /// storages line up
+    fn zip_with<F>(&self, rhs: &Self, f: F) -> Result<Self, MatrixError>
+    where
+        T: Copy,
+        F: Fn(T, T) -> T,
+    {
+        check_same_shape(self, rhs)?;
+        Ok(Self {
+            arr: self
+                .arr
+                .iter()
+                .zip(&rhs.arr)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+            rows: self.rows,
+            cols: self.cols,
+        })
+    }
+
+    /// combines `rhs` into `self` element by element without allocating
+    fn zip_assign<F>(&mut self, rhs: &Self, f: F) -> Result<(), MatrixError>
+    where
+        T: Copy,
+        F: Fn(&mut T, T),
+    {
+        check_same_shape(self, rhs)?;
+        for (a, &b) in self.arr.iter_mut().zip(&rhs.arr) {
+            f(a, b);
+        }
+        Ok(())
+    }
+
+    /// the transpose of a column-major matrix is the same flat storage read as row-major,
+    /// so the elements are copied over as they are without being reordered
+    pub fn transpose(&self) -> MatrixRowMajor<T>
+    where
+        T: Clone,
+    {
+        MatrixRowMajor {
+            arr: self.arr.clone(),
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// same as `transpose` but reuses the storage instead of copying it
+    pub fn into_transpose(self) -> MatrixRowMajor<T> {
+        MatrixRowMajor {
+            arr: self.arr,
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// element in arr[i][j] position without any bounds checking, meant for hot loops where
+    /// the indices are already known to be valid
+    ///
+    /// # Safety
+    ///
+    /// `i` has to be smaller than `rows()` and `j` smaller than `cols()`
+    pub unsafe fn get_unchecked(&self, i: usize, j: usize) -> T
+    where
+        T: Clone,
+    {
+        // SAFETY: the caller guarantees the position is inside the matrix
+        unsafe { self.arr.get_unchecked(j * self.rows + i).clone() }
+    }
+
+    /// new matrix of the same shape and layout with `f` applied to every element
+    pub fn map<U>(&self, f: impl FnMut(T) -> U) -> MatrixColMajor<U>
+    where
+        T: Clone,
+    {
+        MatrixColMajor {
+            arr: self.arr.iter().cloned().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// takes `data` as the storage of a `rows` x `cols` matrix, column by column, without copying it
+    pub fn from_flat(data: Vec<T>, rows: usize, cols: usize) -> Result<Self, MatrixError> {
+        check_flat_len(data.len(), rows, cols)?;
+        Ok(Self {
+            arr: data,
+            rows,
+            cols,
+        })
+    }
+
+    /// the flat storage, column after column, so arr[i][j] is at `j * rows() + i`
+    pub fn as_slice(&self) -> &[T] {
+        &self.arr
+    }
+
+    /// borrowed window onto the rows `row_range` and columns `col_range`, unlike `submatrix`
+    /// nothing is copied
+    pub fn view(
+        &self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> Result<MatrixView<'_, T>, MatrixError> {
+        check_range(&row_range, self.rows)?;
+        check_range(&col_range, self.cols)?;
+        Ok(MatrixView {
+            window: Window::new(row_range, col_range, 1, self.rows),
+            arr: &self.arr,
+        })
+    }
+
+    /// writable window onto the rows `row_range` and columns `col_range`, the writes go
+    /// straight into this matrix
+    pub fn view_mut(
+        &mut self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> Result<MatrixViewMut<'_, T>, MatrixError> {
+        check_range(&row_range, self.rows)?;
+        check_range(&col_range, self.cols)?;
+        Ok(MatrixViewMut {
+            window: Window::new(row_range, col_range, 1, self.rows),
+            arr: &mut self.arr,
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for MatrixColMajor<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        match self.offset(i, j) {
+            Ok(offset) => &self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for MatrixColMajor<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        match self.offset(i, j) {
+            Ok(offset) => &mut self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> fmt::Display for MatrixColMajor<T>
+where
+    T: Clone + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_grid(self, f, None, 1)
+    }
+}
+
+/// how one dimension of a blocky matrix is cut into blocks
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Split {
+    /// blocks of this length, the last one is shorter when the dimension isn't a multiple of it
+    Size(usize),
+    /// this many blocks of lengths at most one apart, the first ones get the extra row or
+    /// column and there are empty ones only when there are more blocks than rows or columns
+    Count(usize),
+}
+
+impl Split {
+    /// number of blocks along a dimension of length `len`
+    fn blocks(self, len: usize) -> usize {
+        match self {
+            Split::Size(size) => len.div_ceil(size),
+            Split::Count(count) => count,
+        }
+    }
+
+    /// where block `b` starts along a dimension of length `len`
+    fn start(self, b: usize, len: usize) -> usize {
+        match self {
+            Split::Size(size) => (b * size).min(len),
+            Split::Count(count) => b * (len / count) + b.min(len % count),
+        }
+    }
+
+    /// block holding position `i` of a dimension of length `len` and the position inside
+    /// it, `i` has to be smaller than `len`
+    fn locate(self, i: usize, len: usize) -> (usize, usize) {
+        match self {
+            Split::Size(size) => (i / size, i % size),
+            Split::Count(count) => {
+                let (size, extra) = (len / count, len % count);
+                let long = extra * (size + 1);
+                if i < long {
+                    (i / (size + 1), i % (size + 1))
+                } else {
+                    (extra + (i - long) / size, (i - long) % size)
+                }
+            }
+        }
+    }
+}
+
+/// splits the matrix into a grid of blocks, either of a fixed size with the blocks on the
+/// bottom/right edges smaller when a dimension isn't a multiple of it, or a fixed number of
+/// blocks spread as evenly as possible. by default there are four blocks around the middle
+/// row and the middle column, when a dimension is odd the top/left blocks get the extra
+/// row/column. the blocking only kicks in from 2 rows or columns on, below that the
+/// dimension stays in one block
+#[derive(Clone)]
+pub struct MatrixBlocky<T> {
+    arr: Vec<MatrixRowMajor<T>>,
+    rows: usize,
+    cols: usize,
+    row_split: Split,
+    col_split: Split,
+    grid_rows: usize,
+    grid_cols: usize,
+}
+impl<T> Matrix<T> for MatrixBlocky<T>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        Self {
+            arr: Vec::new(),
+            rows: 0,
+            cols: 0,
+            row_split: Split::Count(0),
+            col_split: Split::Count(0),
+            grid_rows: 0,
+            grid_cols: 0,
+        }
+    }
+
+    fn new(slice: &[Vec<T>]) -> Self {
+        Self::try_new(slice).unwrap()
+    }
+
+    fn try_new(slice: &[Vec<T>]) -> Result<Self, MatrixError> {
+        check_rectangular(slice)?;
+        if slice.is_empty() {
+            return Ok(Self::default());
+        }
+        let (rows, cols) = (slice.len(), slice[0].len());
+        Ok(Self::copied(
+            slice,
+            Split::Count(rows.min(2)),
+            Split::Count(cols.min(2)),
+        ))
+    }
+
+    /// a dimension smaller than 2 isn't split, so a 1x1 matrix is a single block and an empty
+    /// one has no block at all
+    fn from_fn(rows: usize, cols: usize, f: impl FnMut(usize, usize) -> T) -> Self {
+        Self::tiled(
+            rows,
+            cols,
+            Split::Count(rows.min(2)),
+            Split::Count(cols.min(2)),
+            f,
+        )
+    }
+
+    fn get(&self, i: usize, j: usize) -> Result<T, MatrixError> {
+        let (block, i, j) = self.locate(i, j)?;
+        self.arr[block].get(i, j)
+    }
+
+    fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        let (block, i, j) = self.locate(i, j)?;
+        self.arr[block].set(i, j, value)
+    }
+
+    fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError> {
+        let (block, i, j) = self.locate(i, j)?;
+        self.arr[block].get_mut(i, j)
+    }
+
+    /// multiplies block by block, C_ij = sum of A_ik * B_kj, so every product works on one
+    /// block of each operand at a time. the column split of `self` has to be the same as the
+    /// row split of `rhs` for the blocks to line up, otherwise it goes element by element
+    fn mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default,
+    {
+        check_mul_shape(self, rhs)?;
+        if self.arr.is_empty()
+            || rhs.arr.is_empty()
+            || self.col_split.blocks(self.cols) != rhs.row_split.blocks(rhs.rows)
+            || (0..self.grid_cols).any(|k| self.arr[k].cols != rhs.arr[k * rhs.grid_cols].rows)
+        {
+            return Ok(Self::from_fn(self.rows, rhs.cols, |i, j| {
+                (0..self.cols).fold(T::default(), |acc, k| acc + self[(i, k)] * rhs[(k, j)])
+            }));
+        }
+        let mut arr = Vec::with_capacity(self.grid_rows * rhs.grid_cols);
+        for bi in 0..self.grid_rows {
+            for bj in 0..rhs.grid_cols {
+                let mut block = self.arr[bi * self.grid_cols].mul(&rhs.arr[bj])?;
+                for k in 1..self.grid_cols {
+                    let product =
+                        self.arr[bi * self.grid_cols + k].mul(&rhs.arr[k * rhs.grid_cols + bj])?;
+                    block = block.add(&product)?;
+                }
+                arr.push(block);
+            }
+        }
+        Ok(Self {
+            arr,
+            rows: self.rows,
+            cols: rhs.cols,
+            row_split: self.row_split,
+            col_split: rhs.col_split,
+            grid_rows: self.grid_rows,
+            grid_cols: rhs.grid_cols,
+        })
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Sub<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+
+    fn hadamard(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        self.zip_with(rhs, |a, b| a * b)
+    }
+
+    fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        self.with_blocks(
+            self.arr
+                .iter()
+                .map(|block| block.scalar_mul(scalar))
+                .collect(),
+        )
+    }
+
+    fn row(&self, i: usize) -> Result<Vec<T>, MatrixError> {
+        if i >= self.rows {
+            return Err(index_error(false, i, 0, self.rows, self.cols));
+        }
+        Ok(self.block_row(i).cloned().collect())
+    }
+
+    fn col(&self, j: usize) -> Result<Vec<T>, MatrixError> {
+        if j >= self.cols {
+            return Err(index_error(true, 0, j, self.rows, self.cols));
+        }
+        let (bj, j) = self.col_split.locate(j, self.cols);
+        let mut col = Vec::with_capacity(self.rows);
+        for bi in 0..self.grid_rows {
+            col.extend(self.arr[bi * self.grid_cols + bj].col(j)?);
+        }
+        Ok(col)
+    }
+
+    fn iter_row_major(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.rows).flat_map(move |i| self.block_row(i).cloned())
+    }
+
+    /// a row goes through several blocks so it's swapped one element at a time
+    fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for i in [a, b] {
+            if i >= self.rows {
+                return Err(index_error(false, i, 0, self.rows, self.cols));
+            }
+        }
+        for j in 0..self.cols {
+            let (x, y) = (self.get(a, j)?, self.get(b, j)?);
+            self.set(a, j, y)?;
+            self.set(b, j, x)?;
+        }
+        Ok(())
+    }
+
+    /// a column goes through several blocks so it's swapped one element at a time
+    fn swap_cols(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for j in [a, b] {
+            if j >= self.cols {
+                return Err(index_error(true, 0, j, self.rows, self.cols));
+            }
+        }
+        for i in 0..self.rows {
+            let (x, y) = (self.get(i, a)?, self.get(i, b)?);
+            self.set(i, a, y)?;
+            self.set(i, b, x)?;
+        }
+        Ok(())
+    }
+
+    fn fill(&mut self, value: T) {
+        for block in &mut self.arr {
+            block.fill(value.clone());
+        }
+    }
+
+    fn apply<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        for block in &mut self.arr {
+            block.apply(&mut f);
+        }
+    }
+
+    /// keeps the way the matrix is cut, the same block size or the same number of blocks
+    /// spread over the new shape, only an empty matrix gets the default split
+    fn resize(&mut self, new_rows: usize, new_cols: usize, fill: T) {
+        let keep = |split: Split, len: usize| match split {
+            Split::Count(0) => Split::Count(len.min(2)),
+            split => split,
+        };
+        let resized = Self::tiled(
+            new_rows,
+            new_cols,
+            keep(self.row_split, new_rows),
+            keep(self.col_split, new_cols),
+            |i, j| self.try_get(i, j).unwrap_or_else(|| fill.clone()),
+        );
+        *self = resized;
+    }
+}
+
+impl<T> MatrixBlocky<T> {
+    /// tiles the matrix into `block x block` blocks, the blocks on the bottom/right edges are
+    /// smaller when a dimension isn't a multiple of `block`
+    ///
+    /// # Panics
+    ///
+    /// when the rows don't all have the same length or `block` is zero
+    pub fn with_block_size(slice: &[Vec<T>], block: usize) -> Self
+    where
+        T: Clone,
+    {
+        assert!(block > 0, "the block size has to be at least 1");
+        check_rectangular(slice).unwrap();
+        Self::copied(slice, Split::Size(block), Split::Size(block))
+    }
+
+    /// splits the matrix into a `grid_rows x grid_cols` grid of blocks whose sizes are at most
+    /// one apart, the first `rows % grid_rows` block rows get one row more than the others and
+    /// the same goes for the columns, a block is only empty when there are more blocks than
+    /// rows or columns
+    ///
+    /// # Panics
+    ///
+    /// when the rows don't all have the same length or the grid has no blocks
+    pub fn with_grid(slice: &[Vec<T>], grid_rows: usize, grid_cols: usize) -> Self
+    where
+        T: Clone,
+    {
+        assert!(
+            grid_rows > 0 && grid_cols > 0,
+            "the grid has to have at least one block"
+        );
+        check_rectangular(slice).unwrap();
+        Self::copied(slice, Split::Count(grid_rows), Split::Count(grid_cols))
+    }
+
+    /// builds the grid of blocks the two splits cut the `rows` x `cols` matrix into
+    fn tiled(
+        rows: usize,
+        cols: usize,
+        row_split: Split,
+        col_split: Split,
+        mut f: impl FnMut(usize, usize) -> T,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let (grid_rows, grid_cols) = (row_split.blocks(rows), col_split.blocks(cols));
+        let mut arr = Vec::with_capacity(grid_rows * grid_cols);
+        for bi in 0..grid_rows {
+            let top = row_split.start(bi, rows);
+            let height = row_split.start(bi + 1, rows) - top;
+            for bj in 0..grid_cols {
+                let left = col_split.start(bj, cols);
+                let width = col_split.start(bj + 1, cols) - left;
+                arr.push(MatrixRowMajor::from_fn(height, width, |i, j| {
+                    f(top + i, left + j)
+                }));
+            }
+        }
+        Self {
+            arr,
+            rows,
+            cols,
+            row_split,
+            col_split,
+            grid_rows,
+            grid_cols,
+        }
+    }
+
+    /// same tiling as `tiled` but each block copies its part of every source row as a whole
+    /// slice into storage reserved up front, `slice` has to be rectangular
+    fn copied(slice: &[Vec<T>], row_split: Split, col_split: Split) -> Self
+    where
+        T: Clone,
+    {
+        let (rows, cols) = (slice.len(), slice.first().map_or(0, Vec::len));
+        let (grid_rows, grid_cols) = (row_split.blocks(rows), col_split.blocks(cols));
+        let mut arr = Vec::with_capacity(grid_rows * grid_cols);
+        for bi in 0..grid_rows {
+            let top = row_split.start(bi, rows);
+            let height = row_split.start(bi + 1, rows) - top;
+            for bj in 0..grid_cols {
+                let left = col_split.start(bj, cols);
+                let width = col_split.start(bj + 1, cols) - left;
+                let mut block = Vec::with_capacity(height * width);
+                for row in &slice[top..top + height] {
+                    block.extend_from_slice(&row[left..left + width]);
+                }
+                arr.push(MatrixRowMajor {
+                    arr: block,
+                    rows: height,
+                    cols: width,
+                });
+            }
+        }
+        Self {
+            arr,
+            rows,
+            cols,
+            row_split,
+            col_split,
+            grid_rows,
+            grid_cols,
+        }
+    }
+
+    /// matrix with the same shape and the same tiling as `self` made of the given blocks
+    fn with_blocks<U>(&self, arr: Vec<MatrixRowMajor<U>>) -> MatrixBlocky<U> {
+        MatrixBlocky {
+            arr,
+            rows: self.rows,
+            cols: self.cols,
+            row_split: self.row_split,
+            col_split: self.col_split,
+            grid_rows: self.grid_rows,
+            grid_cols: self.grid_cols,
+        }
+    }
+
+    /// elements of row `i` going through every block it crosses, `i` has to be in bounds
+    fn block_row(&self, i: usize) -> impl Iterator<Item = &T> + '_ {
+        let (bi, i) = self.row_split.locate(i, self.rows);
+        self.arr[bi * self.grid_cols..(bi + 1) * self.grid_cols]
+            .iter()
+            .flat_map(move |block| block.row_slice(i))
+    }
+
+    /// index of the block holding arr[i][j] and the position inside that block
+    fn locate(&self, i: usize, j: usize) -> Result<(usize, usize, usize), MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        let (bi, i) = self.row_split.locate(i, self.rows);
+        let (bj, j) = self.col_split.locate(j, self.cols);
+        Ok((bi * self.grid_cols + bj, i, j))
+    }
+
+    /// combines the two matrices element by element, block by block when both are tiled the
+    /// same way and through the indices otherwise
+    fn zip_with<F>(&self, rhs: &Self, f: F) -> Result<Self, MatrixError>
+    where
+        T: Copy,
+        F: Fn(T, T) -> T,
+    {
+        check_same_shape(self, rhs)?;
+        if !self.same_tiling(rhs) {
+            return Ok(Self::tiled(
+                self.rows,
+                self.cols,
+                self.row_split,
+                self.col_split,
+                |i, j| f(self[(i, j)], rhs[(i, j)]),
+            ));
+        }
+        Ok(self.with_blocks(
+            self.arr
+                .iter()
+                .zip(&rhs.arr)
+                .map(|(a, b)| a.zip_with(b, &f))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    /// combines `rhs` into `self` element by element without allocating, block by block when
+    /// both are tiled the same way and through the indices otherwise
+    fn zip_assign<F>(&mut self, rhs: &Self, f: F) -> Result<(), MatrixError>
+    where
+        T: Copy,
+        F: Fn(&mut T, T),
+    {
+        check_same_shape(self, rhs)?;
+        if !self.same_tiling(rhs) {
+            for i in 0..self.rows {
+                for j in 0..self.cols {
+                    f(&mut self[(i, j)], rhs[(i, j)]);
+                }
+            }
+            return Ok(());
+        }
+        for (a, b) in self.arr.iter_mut().zip(&rhs.arr) {
+            a.zip_assign(b, &f)?;
+        }
+        Ok(())
+    }
+
+    /// true when both matrices are cut into blocks at the same places
+    fn same_tiling(&self, other: &Self) -> bool {
+        (self.grid_rows, self.grid_cols) == (other.grid_rows, other.grid_cols)
+            && self
+                .arr
+                .iter()
+                .zip(&other.arr)
+                .all(|(a, b)| (a.rows, a.cols) == (b.rows, b.cols))
+    }
+
+    /// element in arr[i][j] position without any bounds checking, meant for hot loops where
+    /// the indices are already known to be valid
+    ///
+    /// # Safety
+    ///
+    /// `i` has to be smaller than `rows()` and `j` smaller than `cols()`
+    pub unsafe fn get_unchecked(&self, i: usize, j: usize) -> T
+    where
+        T: Clone,
+    {
+        let (bi, i) = self.row_split.locate(i, self.rows);
+        let (bj, j) = self.col_split.locate(j, self.cols);
+        // SAFETY: a position inside the matrix is inside the block it falls in
+        unsafe {
+            self.arr
+                .get_unchecked(bi * self.grid_cols + bj)
+                .get_unchecked(i, j)
+        }
+    }
+
+    /// new matrix of the same shape and layout with `f` applied to every element
+    pub fn map<U>(&self, mut f: impl FnMut(T) -> U) -> MatrixBlocky<U>
+    where
+        T: Clone,
+    {
+        self.with_blocks(self.arr.iter().map(|block| block.map(&mut f)).collect())
+    }
+
+    /// the storage is only one flat row major buffer when the whole matrix is a single
+    /// block, otherwise every block has its own buffer and there is no such view
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match &self.arr[..] {
+            [block] => Some(block.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// every block from left to right and top to bottom of the grid
+    pub fn blocks(&self) -> impl Iterator<Item = &MatrixRowMajor<T>> + '_ {
+        self.arr.iter()
+    }
+
+    /// the block in row `bi` and column `bj` of the grid
+    pub fn block_at(&self, bi: usize, bj: usize) -> Result<&MatrixRowMajor<T>, MatrixError> {
+        if bi >= self.grid_rows || bj >= self.grid_cols {
+            return Err(MatrixError::BlockIndexing {
+                bi,
+                bj,
+                grid_rows: self.grid_rows,
+                grid_cols: self.grid_cols,
+            });
+        }
+        Ok(&self.arr[bi * self.grid_cols + bj])
+    }
+}
+
+impl<T> Index<(usize, usize)> for MatrixBlocky<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        match self.locate(i, j) {
+            Ok((block, i, j)) => &self.arr[block][(i, j)],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for MatrixBlocky<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        match self.locate(i, j) {
+            Ok((block, i, j)) => &mut self.arr[block][(i, j)],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> fmt::Display for MatrixBlocky<T>
+where
+    T: Clone + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_grid(self, f, None, 1)
+    }
+}
+
+/// keeps only the nonzero elements, keyed by their (i, j) position, every position that is
+/// not stored is zero
+#[derive(Debug, Clone)]
+pub struct MatrixSparse<T> {
+    arr: BTreeMap<(usize, usize), T>,
+    rows: usize,
+    cols: usize,
+}
+impl<T> Matrix<T> for MatrixSparse<T>
+where
+    T: Clone + Zero + PartialEq,
+{
+    fn default() -> Self {
+        Self {
+            arr: BTreeMap::new(),
+            rows: 0,
+            cols: 0,
+        }
+    }
+
+    fn new(slice: &[Vec<T>]) -> Self {
+        Self::try_new(slice).unwrap()
+    }
+
+    fn try_new(slice: &[Vec<T>]) -> Result<Self, MatrixError> {
+        check_rectangular(slice)?;
+        if slice.is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self::from_fn(slice.len(), slice[0].len(), |i, j| {
+            slice[i][j].clone()
+        }))
+    }
+
+    /// `f` is still called for every position but only the nonzero results are kept
+    fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let mut arr = BTreeMap::new();
+        for i in 0..rows {
+            for j in 0..cols {
+                let value = f(i, j);
+                if value != T::zero() {
+                    arr.insert((i, j), value);
+                }
+            }
+        }
+        Self { arr, rows, cols }
+    }
+
+    fn get(&self, i: usize, j: usize) -> Result<T, MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        Ok(self.arr.get(&(i, j)).cloned().unwrap_or_else(T::zero))
+    }
+
+    /// a zero removes the element from the storage
+    fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        if value == T::zero() {
+            self.arr.remove(&(i, j));
+        } else {
+            self.arr.insert((i, j), value);
+        }
+        Ok(())
+    }
+
+    /// the one place an explicit zero can end up stored: a position that is not stored gets a
+    /// zero so there is something to point at, and it stays until a `set` or a rebuild drops
+    /// it, `stored` leaves it out of the count
+    fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        Ok(self.arr.entry((i, j)).or_insert_with(T::zero))
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn row(&self, i: usize) -> Result<Vec<T>, MatrixError> {
+        if i >= self.rows {
+            return Err(index_error(false, i, 0, self.rows, self.cols));
+        }
+        Ok((0..self.cols).map(|j| self.get(i, j).unwrap()).collect())
+    }
+
+    fn col(&self, j: usize) -> Result<Vec<T>, MatrixError> {
+        if j >= self.cols {
+            return Err(index_error(true, 0, j, self.rows, self.cols));
+        }
+        Ok((0..self.rows).map(|i| self.get(i, j).unwrap()).collect())
+    }
+
+    fn iter_row_major(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.rows).flat_map(move |i| (0..self.cols).map(move |j| self.get(i, j).unwrap()))
+    }
+
+    /// only the stored elements of the two rows are moved
+    fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for i in [a, b] {
+            if i >= self.rows {
+                return Err(index_error(false, i, 0, self.rows, self.cols));
+            }
+        }
+        self.remap(|(i, j)| match i {
+            i if i == a => (b, j),
+            i if i == b => (a, j),
+            _ => (i, j),
+        });
+        Ok(())
+    }
+
+    /// only the stored elements of the two columns are moved
+    fn swap_cols(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for j in [a, b] {
+            if j >= self.cols {
+                return Err(index_error(true, 0, j, self.rows, self.cols));
+            }
+        }
+        self.remap(|(i, j)| match j {
+            j if j == a => (i, b),
+            j if j == b => (i, a),
+            _ => (i, j),
+        });
+        Ok(())
+    }
+
+    fn fill(&mut self, value: T) {
+        *self = Self::from_fn(self.rows, self.cols, |_, _| value.clone());
+    }
+
+    /// `f` runs on every position, the zeros too, and the elements that end up zero are
+    /// dropped from the storage
+    fn apply<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        *self = Self::from_fn(self.rows, self.cols, |i, j| {
+            let mut value = self.get(i, j).unwrap();
+            f(&mut value);
+            value
+        });
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        check_same_shape(self, rhs)?;
+        let mut sum = self.clone();
+        for (&(i, j), &value) in &rhs.arr {
+            sum.set(i, j, sum.get(i, j)? + value)?;
+        }
+        Ok(sum)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Sub<Output = T>,
+    {
+        check_same_shape(self, rhs)?;
+        let mut difference = self.clone();
+        for (&(i, j), &value) in &rhs.arr {
+            difference.set(i, j, difference.get(i, j)? - value)?;
+        }
+        Ok(difference)
+    }
+
+    /// only the positions stored in both matrices can be nonzero
+    fn hadamard(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        check_same_shape(self, rhs)?;
+        let mut product = Self {
+            arr: BTreeMap::new(),
+            rows: self.rows,
+            cols: self.cols,
+        };
+        for (&(i, j), &value) in &self.arr {
+            if let Some(&other) = rhs.arr.get(&(i, j)) {
+                product.set(i, j, value * other)?;
+            }
+        }
+        Ok(product)
+    }
+
+    fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        let mut product = Self {
+            arr: BTreeMap::new(),
+            rows: self.rows,
+            cols: self.cols,
+        };
+        for (&(i, j), &value) in &self.arr {
+            product.set(i, j, value * scalar).unwrap();
+        }
+        product
+    }
+
+    /// every stored `(i, k)` of `self` meets only the stored elements of row `k` of `rhs`, so
+    /// the work grows with the stored elements and not with the shape
+    fn mul(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default,
+    {
+        check_mul_shape(self, rhs)?;
+        let mut arr = BTreeMap::new();
+        for (&(i, k), &a) in &self.arr {
+            for (&(_, j), &b) in rhs.arr.range((k, 0)..(k + 1, 0)) {
+                let sum = arr.entry((i, j)).or_insert_with(T::zero);
+                *sum = *sum + a * b;
+            }
+        }
+        arr.retain(|_, value| *value != T::zero());
+        Ok(Self {
+            arr,
+            rows: self.rows,
+            cols: rhs.cols,
+        })
+    }
+}
+
+impl<T: Zero + PartialEq> MatrixSparse<T> {
+    /// number of nonzero elements actually stored
+    pub fn stored(&self) -> usize {
+        self.arr
+            .values()
+            .filter(|value| **value != T::zero())
+            .count()
+    }
+}
+
+impl<T> MatrixSparse<T> {
+    /// moves every stored element to the position `f` gives for it
+    fn remap(&mut self, f: impl Fn((usize, usize)) -> (usize, usize)) {
+        let arr = std::mem::take(&mut self.arr);
+        self.arr = arr.into_iter().map(|(k, v)| (f(k), v)).collect();
+    }
+}
+
+impl<T> fmt::Display for MatrixSparse<T>
+where
+    T: Clone + Zero + PartialEq + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_grid(self, f, None, 1)
+    }
+}
+
+/// keeps only the `lower` diagonals below and the `upper` diagonals above the main one, row
+/// by row and only the part of the band inside the matrix, so even a full band stores no more
+/// than the dense layout, every position outside the band is zero
+#[derive(Debug, Clone)]
+pub struct MatrixBanded<T> {
+    arr: Vec<T>,
+    /// where each row starts in `arr`, with one more entry for the end of the last row
+    starts: Vec<usize>,
+    rows: usize,
+    cols: usize,
+    lower: usize,
+    upper: usize,
+}
+impl<T> Matrix<T> for MatrixBanded<T>
+where
+    T: Clone + Zero + PartialEq,
+{
+    fn default() -> Self {
+        Self {
+            arr: Vec::new(),
+            starts: vec![0],
+            rows: 0,
+            cols: 0,
+            lower: 0,
+            upper: 0,
+        }
+    }
+
+    fn new(slice: &[Vec<T>]) -> Self {
+        Self::try_new(slice).unwrap()
+    }
+
+    /// the band is the narrowest one holding every nonzero element of `slice`
+    fn try_new(slice: &[Vec<T>]) -> Result<Self, MatrixError> {
+        check_rectangular(slice)?;
+        if slice.is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self::from_fn(slice.len(), slice[0].len(), |i, j| {
+            slice[i][j].clone()
+        }))
+    }
+
+    /// `f` is called for every position and the band is the narrowest one holding every
+    /// nonzero result
+    fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let mut values = Vec::new();
+        let (mut lower, mut upper) = (0, 0);
+        for i in 0..rows {
+            for j in 0..cols {
+                let value = f(i, j);
+                if value != T::zero() {
+                    lower = lower.max(i.saturating_sub(j));
+                    upper = upper.max(j.saturating_sub(i));
+                    values.push((i, j, value));
+                }
+            }
+        }
+        let mut banded = Self::banded(rows, cols, lower, upper, |_, _| T::zero());
+        for (i, j, value) in values {
+            let k = banded.slot(i, j).unwrap();
+            banded.arr[k] = value;
+        }
+        banded
+    }
+
+    fn get(&self, i: usize, j: usize) -> Result<T, MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        Ok(match self.slot(i, j) {
+            Some(k) => self.arr[k].clone(),
+            None => T::zero(),
+        })
+    }
+
+    /// a nonzero outside the band widens the band to reach it
+    fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        if self.slot(i, j).is_none() {
+            if value == T::zero() {
+                return Ok(());
+            }
+            self.widen(i, j);
+        }
+        let k = self.slot(i, j).unwrap();
+        self.arr[k] = value;
+        Ok(())
+    }
+
+    /// a position outside the band widens the band so there is something to point at
+    fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        if self.slot(i, j).is_none() {
+            self.widen(i, j);
+        }
+        let k = self.slot(i, j).unwrap();
+        Ok(&mut self.arr[k])
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn row(&self, i: usize) -> Result<Vec<T>, MatrixError> {
+        if i >= self.rows {
+            return Err(index_error(false, i, 0, self.rows, self.cols));
+        }
+        Ok((0..self.cols).map(|j| self.get(i, j).unwrap()).collect())
+    }
+
+    fn col(&self, j: usize) -> Result<Vec<T>, MatrixError> {
+        if j >= self.cols {
+            return Err(index_error(true, 0, j, self.rows, self.cols));
+        }
+        Ok((0..self.rows).map(|i| self.get(i, j).unwrap()).collect())
+    }
+
+    fn iter_row_major(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.rows).flat_map(move |i| (0..self.cols).map(move |j| self.get(i, j).unwrap()))
+    }
+
+    /// the band is rebuilt since the swapped rows generally land off their diagonals
+    fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for i in [a, b] {
+            if i >= self.rows {
+                return Err(index_error(false, i, 0, self.rows, self.cols));
+            }
+        }
+        *self = Self::from_fn(self.rows, self.cols, |i, j| match i {
+            i if i == a => self.get(b, j).unwrap(),
+            i if i == b => self.get(a, j).unwrap(),
+            _ => self.get(i, j).unwrap(),
+        });
+        Ok(())
+    }
+
+    /// the band is rebuilt since the swapped columns generally land off their diagonals
+    fn swap_cols(&mut self, a: usize, b: usize) -> Result<(), MatrixError> {
+        for j in [a, b] {
+            if j >= self.cols {
+                return Err(index_error(true, 0, j, self.rows, self.cols));
+            }
+        }
+        *self = Self::from_fn(self.rows, self.cols, |i, j| match j {
+            j if j == a => self.get(i, b).unwrap(),
+            j if j == b => self.get(i, a).unwrap(),
+            _ => self.get(i, j).unwrap(),
+        });
+        Ok(())
+    }
+
+    fn fill(&mut self, value: T) {
+        *self = Self::from_fn(self.rows, self.cols, |_, _| value.clone());
+    }
+
+    /// `f` runs on every position, the zeros outside the band too, and the band is rebuilt
+    /// around the results
+    fn apply<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        *self = Self::from_fn(self.rows, self.cols, |i, j| {
+            let mut value = self.get(i, j).unwrap();
+            f(&mut value);
+            value
+        });
+    }
+
+    /// the sum keeps the wider of the two bands
+    fn add(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        check_same_shape(self, rhs)?;
+        let (lower, upper) = (self.lower.max(rhs.lower), self.upper.max(rhs.upper));
+        Ok(Self::banded(self.rows, self.cols, lower, upper, |i, j| {
+            self.get(i, j).unwrap() + rhs.get(i, j).unwrap()
+        }))
+    }
+
+    /// the difference keeps the wider of the two bands
+    fn sub(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Sub<Output = T>,
+    {
+        check_same_shape(self, rhs)?;
+        let (lower, upper) = (self.lower.max(rhs.lower), self.upper.max(rhs.upper));
+        Ok(Self::banded(self.rows, self.cols, lower, upper, |i, j| {
+            self.get(i, j).unwrap() - rhs.get(i, j).unwrap()
+        }))
+    }
+
+    /// only the positions inside both bands can be nonzero
+    fn hadamard(&self, rhs: &Self) -> Result<Self, MatrixError>
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        check_same_shape(self, rhs)?;
+        let (lower, upper) = (self.lower.min(rhs.lower), self.upper.min(rhs.upper));
+        Ok(Self::banded(self.rows, self.cols, lower, upper, |i, j| {
+            self.get(i, j).unwrap() * rhs.get(i, j).unwrap()
+        }))
+    }
+
+    fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        let mut product = self.clone();
+        for value in product.arr.iter_mut() {
+            *value = *value * scalar;
+        }
+        product
+    }
+}
+
+impl<T> MatrixBanded<T> {
+    /// `rows` x `cols` matrix with `lower` diagonals below and `upper` above the main one,
+    /// `f` is only called for the positions inside that band
+    pub fn banded(
+        rows: usize,
+        cols: usize,
+        lower: usize,
+        upper: usize,
+        mut f: impl FnMut(usize, usize) -> T,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let mut arr = Vec::new();
+        let mut starts = Vec::with_capacity(rows + 1);
+        for i in 0..rows {
+            starts.push(arr.len());
+            arr.extend(band_columns(i, cols, lower, upper).map(|j| f(i, j)));
+        }
+        starts.push(arr.len());
+        Self {
+            arr,
+            starts,
+            rows,
+            cols,
+            lower,
+            upper,
+        }
+    }
+
+    /// number of diagonals stored below and above the main one
+    pub fn bandwidth(&self) -> (usize, usize) {
+        (self.lower, self.upper)
+    }
+
+    /// position of (i, j) in the compact storage, `None` outside the band
+    fn slot(&self, i: usize, j: usize) -> Option<usize> {
+        let columns = band_columns(i, self.cols, self.lower, self.upper);
+        if i >= self.rows || !columns.contains(&j) {
+            return None;
+        }
+        Some(self.starts[i] + j - columns.start)
+    }
+
+    /// grows the band just enough to cover (i, j), keeping every stored element
+    fn widen(&mut self, i: usize, j: usize)
+    where
+        T: Clone + Zero,
+    {
+        let lower = self.lower.max(i.saturating_sub(j));
+        let upper = self.upper.max(j.saturating_sub(i));
+        let old = std::mem::replace(
+            self,
+            Self::banded(self.rows, self.cols, lower, upper, |_, _| T::zero()),
+        );
+        for i in 0..old.rows {
+            for j in 0..old.cols {
+                if let Some(k) = old.slot(i, j) {
+                    let slot = self.slot(i, j).unwrap();
+                    self.arr[slot] = old.arr[k].clone();
+                }
+            }
+        }
+    }
+}
+
+/// columns of row `i` inside a band of `lower` diagonals below and `upper` above the main one,
+/// cut to the `cols` columns of the matrix
+fn band_columns(i: usize, cols: usize, lower: usize, upper: usize) -> Range<usize> {
+    let start = i.saturating_sub(lower).min(cols);
+    start..(i + upper + 1).min(cols).max(start)
+}
+
+impl<T> fmt::Display for MatrixBanded<T>
+where
+    T: Clone + Zero + PartialEq + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_grid(self, f, None, 1)
+    }
+}
+
+/// `R` x `C` matrix with the shape in the type, the elements live inline in `[[T; C]; R]`
+/// so there is no allocation and the index math is known at compile time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatrixConst<T, const R: usize, const C: usize> {
+    arr: [[T; C]; R],
+}
+
+impl<T, const R: usize, const C: usize> MatrixConst<T, R, C> {
+    /// wraps the rows of the matrix, the shape is checked by the type
+    pub fn new(arr: [[T; C]; R]) -> Self {
+        Self { arr }
+    }
+
+    /// builds the matrix where arr[i][j] is `f(i, j)`
+    pub fn from_fn(mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self {
+            arr: std::array::from_fn(|i| std::array::from_fn(|j| f(i, j))),
+        }
+    }
+
+    /// number of rows of the matrix
+    pub const fn rows(&self) -> usize {
+        R
+    }
+
+    /// number of columns of the matrix
+    pub const fn cols(&self) -> usize {
+        C
+    }
+
+    /// element in arr[i][j] position
+    pub fn get(&self, i: usize, j: usize) -> Result<T, MatrixError>
+    where
+        T: Clone,
+    {
+        check_index(i, j, R, C)?;
+        Ok(self.arr[i][j].clone())
+    }
+
+    /// overwrites the element in arr[i][j] position
+    pub fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        check_index(i, j, R, C)?;
+        self.arr[i][j] = value;
+        Ok(())
+    }
+
+    /// matrix product, the inner dimensions are checked by the type so it can't fail
+    pub fn mul<const K: usize>(&self, rhs: &MatrixConst<T, C, K>) -> MatrixConst<T, R, K>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Default,
+    {
+        MatrixConst::from_fn(|i, j| {
+            (0..C).fold(T::default(), |acc, k| acc + self.arr[i][k] * rhs.arr[k][j])
+        })
+    }
+
+    /// element-wise sum
+    pub fn add(&self, rhs: &Self) -> Self
+    where
+        T: Copy + Add<Output = T>,
+    {
+        Self::from_fn(|i, j| self.arr[i][j] + rhs.arr[i][j])
+    }
+
+    /// element-wise difference
+    pub fn sub(&self, rhs: &Self) -> Self
+    where
+        T: Copy + Sub<Output = T>,
+    {
+        Self::from_fn(|i, j| self.arr[i][j] - rhs.arr[i][j])
+    }
+
+    /// multiplies every element by `scalar`
+    pub fn scalar_mul(&self, scalar: T) -> Self
+    where
+        T: Copy + Mul<Output = T>,
+    {
+        Self::from_fn(|i, j| self.arr[i][j] * scalar)
+    }
+
+    /// transpose of the matrix, the shape of the result is swapped in the type
+    pub fn transpose(&self) -> MatrixConst<T, C, R>
+    where
+        T: Clone,
+    {
+        MatrixConst::from_fn(|i, j| self.arr[j][i].clone())
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for MatrixConst<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        if let Err(err) = check_index(i, j, R, C) {
+            panic!("{}", err);
+        }
+        &self.arr[i][j]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for MatrixConst<T, R, C> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        if let Err(err) = check_index(i, j, R, C) {
+            panic!("{}", err);
+        }
+        &mut self.arr[i][j]
+    }
+}
+
+impl<T: Clone> From<MatrixRowMajor<T>> for MatrixColMajor<T> {
+    fn from(src: MatrixRowMajor<T>) -> Self {
+        MatrixColMajor::from_fn(src.rows, src.cols, |i, j| src.arr[i * src.cols + j].clone())
+    }
+}
+
+impl<T: Clone> From<MatrixColMajor<T>> for MatrixRowMajor<T> {
+    fn from(src: MatrixColMajor<T>) -> Self {
+        MatrixRowMajor::from_fn(src.rows, src.cols, |i, j| src.arr[j * src.rows + i].clone())
+    }
+}
+
+impl<T: Clone> From<MatrixRowMajor<T>> for MatrixBlocky<T> {
+    fn from(src: MatrixRowMajor<T>) -> Self {
+        MatrixBlocky::from_fn(src.rows, src.cols, |i, j| src.arr[i * src.cols + j].clone())
+    }
+}
+
+/// true when two matrices of any layouts have the same shape and the same element at every
+/// position, going through `get` so it also works where `T` is not `Clone`
+pub fn same_contents<A, B, T>(a: &A, b: &B) -> bool
+where
+    A: Matrix<T>,
+    B: Matrix<T>,
+    T: PartialEq,
+{
+    a.shape() == b.shape()
+        && (0..a.rows()).all(|i| (0..a.cols()).all(|j| a.get(i, j).ok() == b.get(i, j).ok()))
+}
+
+/// two matrices are equal when they have the same shape and the same element at every
+/// position, however they are stored
+fn eq_logical<T, L, R>(lhs: &L, rhs: &R) -> bool
+where
+    T: Clone + PartialEq,
+    L: Matrix<T>,
+    R: Matrix<T>,
+{
+    lhs.rows() == rhs.rows()
+        && lhs.cols() == rhs.cols()
+        && lhs.iter_row_major().eq(rhs.iter_row_major())
+}
+
+macro_rules! impl_partial_eq {
+    ($lhs:ident => $($rhs:ident),*) => {
+        $(
+            impl<T> PartialEq<$rhs<T>> for $lhs<T>
+            where
+                T: Clone + PartialEq,
+            {
+                fn eq(&self, other: &$rhs<T>) -> bool {
+                    eq_logical(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_eq!(MatrixRowMajor => MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+impl_partial_eq!(MatrixColMajor => MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+impl_partial_eq!(MatrixBlocky => MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+
+/// hashes the shape and then every element in row major order, so matrices that are equal
+/// across layouts hash the same
+macro_rules! impl_hash {
+    ($($matrix:ident),*) => {
+        $(
+            impl<T> Eq for $matrix<T> where T: Clone + Eq {}
+
+            impl<T> Hash for $matrix<T>
+            where
+                T: Clone + Hash,
+            {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.shape().hash(state);
+                    for value in self.iter_row_major() {
+                        value.hash(state);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_hash!(MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+
+macro_rules! impl_assign_ops {
+    ($($matrix:ident),*) => {
+        $(
+            impl<T> AddAssign<&$matrix<T>> for $matrix<T>
+            where
+                T: Copy + Add<Output = T>,
+            {
+                fn add_assign(&mut self, rhs: &$matrix<T>) {
+                    if let Err(err) = self.zip_assign(rhs, |a, b| *a = *a + b) {
+                        panic!("{}", err);
+                    }
+                }
+            }
+
+            impl<T> SubAssign<&$matrix<T>> for $matrix<T>
+            where
+                T: Copy + Sub<Output = T>,
+            {
+                fn sub_assign(&mut self, rhs: &$matrix<T>) {
+                    if let Err(err) = self.zip_assign(rhs, |a, b| *a = *a - b) {
+                        panic!("{}", err);
+                    }
+                }
+            }
+
+            impl<T> MulAssign<T> for $matrix<T>
+            where
+                T: Copy + Mul<Output = T>,
+            {
+                fn mul_assign(&mut self, scalar: T) {
+                    self.apply(|x| *x = *x * scalar);
+                }
+            }
+        )*
+    };
+}
+
+impl_assign_ops!(MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+
+macro_rules! impl_neg {
+    ($($matrix:ident),*) => {
+        $(
+            /// negates the elements in place and gives the matrix back
+            impl<T> Neg for $matrix<T>
+            where
+                T: Copy + Neg<Output = T>,
+            {
+                type Output = $matrix<T>;
+
+                fn neg(mut self) -> $matrix<T> {
+                    self.apply(|x| *x = -*x);
+                    self
+                }
+            }
+
+            impl<T> Neg for &$matrix<T>
+            where
+                T: Copy + Neg<Output = T>,
+            {
+                type Output = $matrix<T>;
+
+                fn neg(self) -> $matrix<T> {
+                    self.map(|x| -x)
+                }
+            }
+        )*
+    };
+}
+
+impl_neg!(MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+
+/// where a view sits in the flat storage of its parent, arr[i][j] of the view is at
+/// `offset + i * row_stride + j * col_stride`
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    offset: usize,
+    row_stride: usize,
+    col_stride: usize,
+    rows: usize,
+    cols: usize,
+}
+
+impl Window {
+    /// the rows `row_range` and columns `col_range` of a matrix stored with the given strides
+    fn new(
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+        row_stride: usize,
+        col_stride: usize,
+    ) -> Self {
+        Self {
+            offset: row_range.start * row_stride + col_range.start * col_stride,
+            row_stride,
+            col_stride,
+            rows: row_range.len(),
+            cols: col_range.len(),
+        }
+    }
+
+    /// position of (i, j) of the view in the parent's storage, checked against the view
+    fn offset(&self, i: usize, j: usize) -> Result<usize, MatrixError> {
+        check_index(i, j, self.rows, self.cols)?;
+        Ok(self.offset + i * self.row_stride + j * self.col_stride)
+    }
+}
+
+/// read only window onto a rectangle of a row or column major matrix, it borrows the
+/// parent's storage so nothing is copied
+#[derive(Debug)]
+pub struct MatrixView<'a, T> {
+    arr: &'a [T],
+    window: Window,
+}
+
+impl<T> Clone for MatrixView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MatrixView<'_, T> {}
+
+impl<T> MatrixView<'_, T> {
+    pub fn get(&self, i: usize, j: usize) -> Result<T, MatrixError>
+    where
+        T: Clone,
+    {
+        Ok(self.arr[self.window.offset(i, j)?].clone())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.window.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.window.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.window.rows, self.window.cols)
+    }
+}
+
+impl<T> Index<(usize, usize)> for MatrixView<'_, T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        match self.window.offset(i, j) {
+            Ok(offset) => &self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// writable window onto a rectangle of a row or column major matrix, every access is
+/// limited to the bounds of the view and goes straight to the parent's storage
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T> {
+    arr: &'a mut [T],
+    window: Window,
+}
+
+impl<T> MatrixViewMut<'_, T> {
+    pub fn get(&self, i: usize, j: usize) -> Result<T, MatrixError>
+    where
+        T: Clone,
+    {
+        Ok(self.arr[self.window.offset(i, j)?].clone())
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), MatrixError> {
+        self.arr[self.window.offset(i, j)?] = value;
+        Ok(())
+    }
+
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, MatrixError> {
+        Ok(&mut self.arr[self.window.offset(i, j)?])
+    }
+
+    pub fn rows(&self) -> usize {
+        self.window.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.window.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.window.rows, self.window.cols)
+    }
+}
+
+impl<T> Index<(usize, usize)> for MatrixViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        match self.window.offset(i, j) {
+            Ok(offset) => &self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for MatrixViewMut<'_, T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        match self.window.offset(i, j) {
+            Ok(offset) => &mut self.arr[offset],
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// iterator over the rows of a matrix, returned by [`Matrix::iter_rows`]
+pub struct Rows<'a, T, M> {
+    matrix: &'a M,
+    next: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T, M> Iterator for Rows<'_, T, M>
+where
+    T: Clone,
+    M: Matrix<T>,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let row = self.matrix.row(self.next).ok()?;
+        self.next += 1;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.matrix.rows() - self.next;
+        (left, Some(left))
+    }
+}
+
+impl<T, M> ExactSizeIterator for Rows<'_, T, M>
+where
+    T: Clone,
+    M: Matrix<T>,
+{
+}
+
+macro_rules! impl_into_iter {
+    ($($matrix:ident),*) => {
+        $(
+            impl<'a, T> IntoIterator for &'a $matrix<T>
+            where
+                T: Clone,
+            {
+                type Item = Vec<T>;
+                type IntoIter = Rows<'a, T, $matrix<T>>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    self.iter_rows()
+                }
+            }
+        )*
+    };
+}
+
+impl_into_iter!(MatrixRowMajor, MatrixColMajor, MatrixBlocky);
+
+#[cfg(test)]
+mod matrix_test {
+    use super::*;
+
+    #[test]
+    fn test_3x4_row_matrix() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixColMajor::new(&arr);
+
+        assert_eq!(6, row.get(1, 1).unwrap());
+        assert_eq!(1, row.get(0, 0).unwrap());
+        assert_eq!(12, row.get(2, 3).unwrap());
+        assert_ne!(14, row.get(5, 9).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_3x4_col_matrix() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let col = MatrixColMajor::new(&arr);
+
+        assert_eq!(6, col.get(1, 1).unwrap());
+        assert_eq!(1, col.get(0, 0).unwrap());
+        assert_eq!(12, col.get(2, 3).unwrap());
+        assert_ne!(14, col.get(5, 9).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_8x12_row_matrix() {
+        let arr = vec![
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+            vec![13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24],
+            vec![25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36],
+            vec![37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48],
+            vec![49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60],
+            vec![61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72],
+            vec![73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84],
+            vec![85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+
+        assert_eq!(28, row.get(2, 3).unwrap());
+        assert_eq!(68, row.get(5, 7).unwrap());
+        assert_eq!(96, row.get(7, 11).unwrap());
+        assert_ne!(14, row.get(5, 9).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_8x12_col_matrix() {
+        let arr = vec![
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+            vec![13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24],
+            vec![25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36],
+            vec![37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48],
+            vec![49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60],
+            vec![61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72],
+            vec![73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84],
+            vec![85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96],
+        ];
+        let col = MatrixColMajor::new(&arr);
+
+        assert_eq!(28, col.get(2, 3).unwrap());
+        assert_eq!(68, col.get(5, 7).unwrap());
+        assert_eq!(96, col.get(7, 11).unwrap());
+        assert_ne!(14, col.get(5, 9).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_6x6_block_matrix() {
+        let arr = vec![
+            vec![1, 2, 3, 4, 5, 6],
+            vec![7, 8, 9, 10, 11, 12],
+            vec![13, 14, 15, 16, 17, 18],
+            vec![19, 20, 21, 22, 23, 24],
+            vec![25, 26, 27, 28, 29, 30],
+            vec![31, 32, 33, 34, 35, 36],
+        ];
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(21, block.get(3, 2).unwrap());
+        assert_eq!(28, block.get(4, 3).unwrap());
+        assert_eq!(11, block.get(1, 4).unwrap());
+        assert_ne!(14, block.get(3, 5).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_row_matrix_column_past_end() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+
+        assert!(matches!(
+            row.get(0, 4),
+            Err(MatrixError::ForbiddenIndexing { .. })
+        ));
+    }
+
+    #[test]
+    fn test_row_matrix_flat_index_past_end() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+
+        assert_eq!(12, row.get(2, 3).unwrap());
+        assert!(matches!(
+            row.get(3, 0),
+            Err(MatrixError::OutOfBoundIndexing { .. })
+        ));
+    }
+
+    #[test]
+    fn test_row_matrix_out_of_bound_fields() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+
+        match row.get(3, 1) {
+            Err(MatrixError::OutOfBoundIndexing {
+                i,
+                j,
+                offset,
+                rows,
+                cols,
+            }) => assert_eq!((3, 1, 13, 3, 4), (i, j, offset, rows, cols)),
+            other => panic!("expected OutOfBoundIndexing, got {:?}", other),
+        }
+        assert_eq!(
+            "Out of bound indexing: (i = 3, j = 1) at offset 13 is outside of the 3x4 matrix",
+            row.get(3, 1).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_col_matrix_forbidden_fields() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let col = MatrixColMajor::new(&arr);
+
+        assert!(matches!(
+            col.get(5, 1),
+            Err(MatrixError::ForbiddenIndexing {
+                i: 5,
+                j: 1,
+                rows: 3,
+                cols: 4
+            })
+        ));
+        assert!(matches!(
+            MatrixBlocky::new(&arr).get(1, 7),
+            Err(MatrixError::ForbiddenIndexing { i: 1, j: 7, .. })
+        ));
+        assert!(matches!(
+            col.row(4),
+            Err(MatrixError::ForbiddenIndexing { i: 4, j: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_empty_row_matrix() {
+        let row = MatrixRowMajor::<i32>::new(&[]);
+
+        assert!(row.arr.is_empty());
+        assert_eq!(0, row.rows);
+        assert_eq!(0, row.cols);
+    }
+
+    #[test]
+    fn test_empty_col_matrix() {
+        let col = MatrixColMajor::<i32>::new(&[]);
+
+        assert!(col.arr.is_empty());
+        assert_eq!(0, col.rows);
+        assert_eq!(0, col.cols);
+    }
+
+    #[test]
+    fn test_empty_block_matrix() {
+        let block = MatrixBlocky::<i32>::new(&[]);
+
+        assert!(block.arr.is_empty());
+        assert_eq!(0, block.grid_rows);
+        assert_eq!(0, block.grid_cols);
+    }
+
+    #[test]
+    fn test_try_new_rectangular() {
+        let arr = vec![
+            vec![1, 2, 3], //
+            vec![4, 5, 6],
+        ];
+
+        assert_eq!(5, MatrixRowMajor::try_new(&arr).unwrap().get(1, 1).unwrap());
+        assert_eq!(5, MatrixColMajor::try_new(&arr).unwrap().get(1, 1).unwrap());
+    }
+
+    #[test]
+    fn test_try_new_jagged() {
+        let arr = vec![
+            vec![1, 2, 3], //
+            vec![4, 5],
+        ];
+
+        assert!(matches!(
+            MatrixRowMajor::try_new(&arr),
+            Err(MatrixError::Jagged(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::try_new(&arr),
+            Err(MatrixError::Jagged(_))
+        ));
+        assert!(matches!(
+            MatrixBlocky::try_new(&arr),
+            Err(MatrixError::Jagged(_))
+        ));
+    }
+
+    #[test]
+    fn test_5x5_block_matrix() {
+        let arr: Vec<Vec<i32>> = (0..5)
+            .map(|i| (0..5).map(|j| i * 5 + j + 1).collect())
+            .collect();
+        let block = MatrixBlocky::new(&arr);
+
+        for (i, row) in arr.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_eq!(value, block.get(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_4x6_block_matrix() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..6).map(|j| i * 6 + j + 1).collect())
+            .collect();
+        let block = MatrixBlocky::new(&arr);
+
+        for (i, row) in arr.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_eq!(value, block.get(i, j).unwrap());
+            }
+        }
+        assert!(block.get(4, 0).is_err());
+        assert!(block.get(0, 6).is_err());
+    }
+
+    #[test]
+    fn test_set_row_matrix() {
+        let mut row = MatrixRowMajor::new(&[vec![1, 2, 3], vec![4, 5, 6]]);
+
+        row.set(1, 2, 60).unwrap();
+        assert_eq!(60, row.get(1, 2).unwrap());
+        assert_eq!(5, row.get(1, 1).unwrap());
+    }
+
+    #[test]
+    fn test_set_col_matrix() {
+        let mut col = MatrixColMajor::new(&[vec![1, 2, 3], vec![4, 5, 6]]);
+
+        col.set(0, 2, 30).unwrap();
+        assert_eq!(30, col.get(0, 2).unwrap());
+        assert_eq!(6, col.get(1, 2).unwrap());
+    }
+
+    #[test]
+    fn test_set_block_matrix() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..4).map(|j| i * 4 + j + 1).collect())
+            .collect();
+        let mut block = MatrixBlocky::new(&arr);
+
+        block.set(3, 1, 140).unwrap();
+        block.set(1, 3, 80).unwrap();
+        assert_eq!(140, block.get(3, 1).unwrap());
+        assert_eq!(80, block.get(1, 3).unwrap());
+        assert_eq!(16, block.get(3, 3).unwrap());
+    }
+
+    #[test]
+    fn test_set_out_of_range() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+
+        assert!(matches!(
+            row.set(0, 3, 0),
+            Err(MatrixError::ForbiddenIndexing { .. })
+        ));
+        assert!(matches!(
+            row.set(2, 0, 0),
+            Err(MatrixError::OutOfBoundIndexing { .. })
+        ));
+        assert!(matches!(
+            col.set(2, 0, 0),
+            Err(MatrixError::ForbiddenIndexing { .. })
+        ));
+        assert!(matches!(
+            col.set(0, 3, 0),
+            Err(MatrixError::OutOfBoundIndexing { .. })
+        ));
+    }
+
+    #[test]
+    fn test_3x4_shape() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(3, row.rows());
+        assert_eq!(4, row.cols());
+        assert_eq!(3, col.rows());
+        assert_eq!(4, col.cols());
+        assert_eq!(3, block.rows());
+        assert_eq!(4, block.cols());
+    }
+
+    /// checks every cell of `m` against `arr` and that the cells just outside are rejected
+    fn assert_matches_slice<M: Matrix<i32>>(m: &M, arr: &[Vec<i32>]) {
+        assert_eq!(arr.len(), m.rows());
+        assert_eq!(arr[0].len(), m.cols());
+        for (i, row) in arr.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_eq!(value, m.get(i, j).unwrap());
+            }
+            assert!(m.get(i, m.cols()).is_err());
+        }
+        for j in 0..m.cols() {
+            assert!(m.get(m.rows(), j).is_err());
+        }
+    }
+
+    fn assert_close<M: Matrix<f64>>(m: &M, arr: &[Vec<f64>]) {
+        assert_eq!((arr.len(), arr[0].len()), m.shape());
+        for (i, row) in arr.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                let got = m.get(i, j).unwrap();
+                assert!(
+                    (value - got).abs() < 1e-9,
+                    "({}, {}): {} != {}",
+                    i,
+                    j,
+                    value,
+                    got
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_2x5_matrix() {
+        let arr: Vec<Vec<i32>> = (0..2)
+            .map(|i| (0..5).map(|j| i * 5 + j + 1).collect())
+            .collect();
+
+        assert_matches_slice(&MatrixRowMajor::new(&arr), &arr);
+        assert_matches_slice(&MatrixColMajor::new(&arr), &arr);
+        assert_matches_slice(&MatrixBlocky::new(&arr), &arr);
+    }
+
+    #[test]
+    fn test_5x2_matrix() {
+        let arr: Vec<Vec<i32>> = (0..5)
+            .map(|i| (0..2).map(|j| i * 2 + j + 1).collect())
+            .collect();
+
+        assert_matches_slice(&MatrixRowMajor::new(&arr), &arr);
+        assert_matches_slice(&MatrixColMajor::new(&arr), &arr);
+        assert_matches_slice(&MatrixBlocky::new(&arr), &arr);
+    }
+
+    #[test]
+    fn test_index_matches_get() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..4).map(|j| i * 4 + j + 1).collect())
+            .collect();
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(row.get(1, 1).unwrap(), row[(1, 1)]);
+        assert_eq!(col.get(1, 1).unwrap(), col[(1, 1)]);
+        assert_eq!(block.get(1, 1).unwrap(), block[(1, 1)]);
+        assert_eq!(block.get(3, 2).unwrap(), block[(3, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Forbidden indexing")]
+    fn test_index_out_of_range_row_matrix() {
+        let row = MatrixRowMajor::new(&[vec![1, 2], vec![3, 4]]);
+        let _ = row[(0, 2)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bound indexing")]
+    fn test_index_out_of_range_block_matrix() {
+        let block = MatrixBlocky::new(&[vec![1, 2], vec![3, 4]]);
+        let _ = block[(2, 0)];
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+
+        row[(1, 2)] = 60;
+        col[(1, 2)] += 60;
+        assert_eq!(60, row.get(1, 2).unwrap());
+        assert_eq!(66, col.get(1, 2).unwrap());
+        assert_eq!(3, col.get(0, 2).unwrap());
+    }
+
+    #[test]
+    fn test_index_mut_block_matrix() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..4).map(|j| i * 4 + j + 1).collect())
+            .collect();
+        let mut block = MatrixBlocky::new(&arr);
+
+        // (1, 1) is the last cell of the top-left block and (2, 2) the first of the bottom-right
+        block[(1, 1)] = -6;
+        block[(2, 2)] = -11;
+        assert_eq!(-6, block.get(1, 1).unwrap());
+        assert_eq!(-11, block.get(2, 2).unwrap());
+        assert_eq!(7, block.get(1, 2).unwrap());
+        assert_eq!(10, block.get(2, 1).unwrap());
+    }
+
+    #[test]
+    fn test_display() {
+        let arr = vec![vec![1, 2], vec![3, 4]];
+
+        assert_eq!("1 2\n3 4", MatrixRowMajor::new(&arr).to_string());
+        assert_eq!("1 2\n3 4", MatrixColMajor::new(&arr).to_string());
+        assert_eq!("1 2\n3 4", MatrixBlocky::new(&arr).to_string());
+    }
+
+    #[test]
+    fn test_display_aligns_columns() {
+        let arr = vec![vec![1, -20, 3], vec![400, 5, 6]];
+        let expected = "  1 -20   3\n\
+                        400   5   6";
+
+        assert_eq!(expected, MatrixRowMajor::new(&arr).to_string());
+        assert_eq!(expected, MatrixColMajor::new(&arr).to_string());
+        assert_eq!(expected, MatrixBlocky::new(&arr).to_string());
+    }
+
+    #[test]
+    fn test_mul() {
+        let lhs = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let rhs = vec![vec![7, 8], vec![9, 10], vec![11, 12]];
+        let expected = vec![vec![58, 64], vec![139, 154]];
+
+        let row = MatrixRowMajor::new(&lhs)
+            .mul(&MatrixRowMajor::new(&rhs))
+            .unwrap();
+        let col = MatrixColMajor::new(&lhs)
+            .mul(&MatrixColMajor::new(&rhs))
+            .unwrap();
+        let block = MatrixBlocky::new(&lhs)
+            .mul(&MatrixBlocky::new(&rhs))
+            .unwrap();
+        assert_matches_slice(&row, &expected);
+        assert_matches_slice(&col, &expected);
+        assert_matches_slice(&block, &expected);
+    }
+
+    #[test]
+    fn test_mul_dimension_mismatch() {
+        let lhs = vec![vec![1, 2, 3], vec![4, 5, 6]];
+
+        assert!(matches!(
+            MatrixRowMajor::new(&lhs).mul(&MatrixRowMajor::new(&lhs)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::new(&lhs).mul(&MatrixColMajor::new(&lhs)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            MatrixBlocky::new(&lhs).mul(&MatrixBlocky::new(&lhs)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let lhs = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let rhs = vec![vec![9, 8, 7], vec![6, 5, 4], vec![3, 2, 1]];
+        let sum = vec![vec![10; 3]; 3];
+        let diff = vec![vec![-8, -6, -4], vec![-2, 0, 2], vec![4, 6, 8]];
+
+        let (a, b) = (MatrixRowMajor::new(&lhs), MatrixRowMajor::new(&rhs));
+        assert_matches_slice(&a.add(&b).unwrap(), &sum);
+        assert_matches_slice(&a.sub(&b).unwrap(), &diff);
+        let (a, b) = (MatrixColMajor::new(&lhs), MatrixColMajor::new(&rhs));
+        assert_matches_slice(&a.add(&b).unwrap(), &sum);
+        assert_matches_slice(&a.sub(&b).unwrap(), &diff);
+        let (a, b) = (MatrixBlocky::new(&lhs), MatrixBlocky::new(&rhs));
+        assert_matches_slice(&a.add(&b).unwrap(), &sum);
+        assert_matches_slice(&a.sub(&b).unwrap(), &diff);
+    }
+
+    #[test]
+    fn test_add_shape_mismatch() {
+        let lhs = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let rhs = vec![vec![1, 2], vec![3, 4], vec![5, 6]];
+
+        assert!(matches!(
+            MatrixRowMajor::new(&lhs).add(&MatrixRowMajor::new(&rhs)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::new(&lhs).sub(&MatrixColMajor::new(&rhs)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            MatrixBlocky::new(&lhs).add(&MatrixBlocky::new(&rhs)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_scalar_mul() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let doubled = vec![vec![2, 4, 6], vec![8, 10, 12], vec![14, 16, 18]];
+
+        assert_matches_slice(&MatrixRowMajor::new(&arr).scalar_mul(2), &doubled);
+        assert_matches_slice(&MatrixColMajor::new(&arr).scalar_mul(2), &doubled);
+        assert_matches_slice(&MatrixBlocky::new(&arr).scalar_mul(2), &doubled);
+    }
+
+    #[test]
+    fn test_scalar_mul_by_zero() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let zeros = vec![vec![0; 3]; 3];
+
+        assert_matches_slice(&MatrixRowMajor::new(&arr).scalar_mul(0), &zeros);
+        assert_matches_slice(&MatrixColMajor::new(&arr).scalar_mul(0), &zeros);
+        assert_matches_slice(&MatrixBlocky::new(&arr).scalar_mul(0), &zeros);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let expected = vec![
+            vec![1, 5, 9], //
+            vec![2, 6, 10],
+            vec![3, 7, 11],
+            vec![4, 8, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+
+        assert_matches_slice(&row.transpose(), &expected);
+        assert_matches_slice(&col.transpose(), &expected);
+        assert_matches_slice(&row.transposed(), &expected);
+        assert_matches_slice(&col.transposed(), &expected);
+        assert_matches_slice(&MatrixBlocky::new(&arr).transposed(), &expected);
+        assert_matches_slice(&row.into_transpose(), &expected);
+        assert_matches_slice(&col.into_transpose(), &expected);
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let arr: Vec<Vec<usize>> = (0..3)
+            .map(|i| (0..4).map(|j| i * 4 + j).collect())
+            .collect();
+
+        let row = MatrixRowMajor::from_fn(3, 4, |i, j| i * 4 + j);
+        let col = MatrixColMajor::from_fn(3, 4, |i, j| i * 4 + j);
+        let block = MatrixBlocky::from_fn(3, 4, |i, j| i * 4 + j);
+        assert_eq!(MatrixRowMajor::new(&arr).arr, row.arr);
+        assert_eq!(MatrixColMajor::new(&arr).arr, col.arr);
+        assert_eq!((3, 4), (row.rows(), row.cols()));
+        assert_eq!((3, 4), (col.rows(), col.cols()));
+        for (i, line) in arr.iter().enumerate() {
+            for (j, &value) in line.iter().enumerate() {
+                assert_eq!(value, block.get(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_identity() {
+        let row = MatrixRowMajor::<i32>::identity(3);
+        let col = MatrixColMajor::<i32>::identity(3);
+        let block = MatrixBlocky::<i32>::identity(3);
+
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1 } else { 0 };
+                assert_eq!(expected, row.get(i, j).unwrap());
+                assert_eq!(expected, col.get(i, j).unwrap());
+                assert_eq!(expected, block.get(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_zeros_ones() {
+        assert_matches_slice(&MatrixRowMajor::zeros(2, 3), &[vec![0; 3], vec![0; 3]]);
+        assert_matches_slice(&MatrixColMajor::zeros(2, 3), &[vec![0; 3], vec![0; 3]]);
+        assert_matches_slice(&MatrixBlocky::zeros(2, 3), &[vec![0; 3], vec![0; 3]]);
+        assert_matches_slice(
+            &MatrixRowMajor::ones(3, 2),
+            &[vec![1; 2], vec![1; 2], vec![1; 2]],
+        );
+        assert_matches_slice(
+            &MatrixColMajor::ones(3, 2),
+            &[vec![1; 2], vec![1; 2], vec![1; 2]],
+        );
+        assert_matches_slice(
+            &MatrixBlocky::ones(3, 2),
+            &[vec![1; 2], vec![1; 2], vec![1; 2]],
+        );
+    }
+
+    #[test]
+    fn test_row_col() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(vec![5, 6, 7, 8], row.row(1).unwrap());
+        assert_eq!(vec![5, 6, 7, 8], col.row(1).unwrap());
+        assert_eq!(vec![9, 10, 11, 12], block.row(2).unwrap());
+        assert_eq!(vec![3, 7, 11], row.col(2).unwrap());
+        assert_eq!(vec![3, 7, 11], col.col(2).unwrap());
+        assert_eq!(vec![4, 8, 12], block.col(3).unwrap());
+        assert_eq!(vec![2], MatrixBlocky::new(&[vec![1, 2, 3]]).col(1).unwrap());
+    }
+
+    #[test]
+    fn test_row_col_out_of_range() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert!(row.row(3).is_err());
+        assert!(row.col(4).is_err());
+        assert!(col.row(3).is_err());
+        assert!(col.col(4).is_err());
+        assert!(block.row(3).is_err());
+        assert!(block.col(4).is_err());
+    }
+
+    #[test]
+    fn test_iter_row_major() {
+        let arr: Vec<Vec<i32>> = (0..5)
+            .map(|i| (0..3).map(|j| i * 3 + j).collect())
+            .collect();
+        let expected: Vec<i32> = (0..15).collect();
+
+        let row: Vec<i32> = MatrixRowMajor::new(&arr).iter_row_major().collect();
+        let col: Vec<i32> = MatrixColMajor::new(&arr).iter_row_major().collect();
+        let block: Vec<i32> = MatrixBlocky::new(&arr).iter_row_major().collect();
+        assert_eq!(expected, row);
+        assert_eq!(expected, col);
+        assert_eq!(expected, block);
+    }
+
+    #[test]
+    fn test_eq_same_layout() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let other = vec![vec![1, 2, 3], vec![4, 0, 6]];
+
+        assert!(MatrixRowMajor::new(&arr) == MatrixRowMajor::new(&arr));
+        assert!(MatrixColMajor::new(&arr) == MatrixColMajor::new(&arr));
+        assert!(MatrixBlocky::new(&arr) == MatrixBlocky::new(&arr));
+        assert!(MatrixRowMajor::new(&arr) != MatrixRowMajor::new(&other));
+        assert!(MatrixColMajor::new(&arr) != MatrixColMajor::new(&other));
+        assert!(MatrixBlocky::new(&arr) != MatrixBlocky::new(&other));
+    }
+
+    #[test]
+    fn test_eq_cross_layout() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert!(row == col);
+        assert!(col == block);
+        assert!(block == row);
+    }
+
+    #[test]
+    fn test_eq_different_shape() {
+        let wide = MatrixRowMajor::new(&[vec![1, 2, 3, 4, 5, 6]]);
+        let tall = MatrixColMajor::new(&[vec![1, 2], vec![3, 4], vec![5, 6]]);
+
+        assert!(wide != MatrixRowMajor::new(&[vec![1, 2, 3], vec![4, 5, 6]]));
+        assert!(wide != tall);
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        let mut row_copy = row.clone();
+        let mut col_copy = col.clone();
+        let mut block_copy = block.clone();
+        row_copy.set(1, 1, 0).unwrap();
+        col_copy.set(1, 1, 0).unwrap();
+        block_copy.set(2, 2, 0).unwrap();
+
+        assert_matches_slice(&row, &arr);
+        assert_matches_slice(&col, &arr);
+        assert_matches_slice(&block, &arr);
+        assert_eq!(0, row_copy.get(1, 1).unwrap());
+        assert_eq!(0, col_copy.get(1, 1).unwrap());
+        assert_eq!(0, block_copy.get(2, 2).unwrap());
+    }
+
+    #[test]
+    fn test_from_conversions() {
+        let wide: Vec<Vec<i32>> = (0..3)
+            .map(|i| (0..4).map(|j| i * 4 + j).collect())
+            .collect();
+        let square: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..4).map(|j| i * 4 + j).collect())
+            .collect();
+
+        for arr in [&wide, &square] {
+            let col = MatrixColMajor::from(MatrixRowMajor::new(arr));
+            let row = MatrixRowMajor::from(MatrixColMajor::new(arr));
+            let block = MatrixBlocky::from(MatrixRowMajor::new(arr));
+            assert_matches_slice(&col, arr);
+            assert_matches_slice(&row, arr);
+            assert_matches_slice(&block, arr);
+        }
+    }
+
+    /// reads every in-range cell through `get_unchecked`, which is sound because the indices
+    /// come from the matrix's own shape
+    fn unchecked_cells<M: Matrix<i32>>(
+        m: &M,
+        read: unsafe fn(&M, usize, usize) -> i32,
+    ) -> Vec<i32> {
+        let mut cells = Vec::new();
+        for i in 0..m.rows() {
+            for j in 0..m.cols() {
+                // SAFETY: i < rows and j < cols
+                cells.push(unsafe { read(m, i, j) });
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_get_unchecked_matches_get() {
+        let arr: Vec<Vec<i32>> = (0..5)
+            .map(|i| (0..4).map(|j| i * 4 + j).collect())
+            .collect();
+        let expected: Vec<i32> = (0..20).collect();
+
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+        assert_eq!(
+            expected,
+            unchecked_cells(&row, MatrixRowMajor::get_unchecked)
+        );
+        assert_eq!(
+            expected,
+            unchecked_cells(&col, MatrixColMajor::get_unchecked)
+        );
+        assert_eq!(
+            expected,
+            unchecked_cells(&block, MatrixBlocky::get_unchecked)
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+
+        assert_eq!(15, MatrixRowMajor::new(&arr).trace().unwrap());
+        assert_eq!(15, MatrixColMajor::new(&arr).trace().unwrap());
+        assert_eq!(15, MatrixBlocky::new(&arr).trace().unwrap());
+    }
+
+    #[test]
+    fn test_trace_not_square() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6]];
+
+        assert!(matches!(
+            MatrixRowMajor::new(&arr).trace(),
+            Err(MatrixError::NotSquare(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::new(&arr).trace(),
+            Err(MatrixError::NotSquare(_))
+        ));
+        assert!(matches!(
+            MatrixBlocky::new(&arr).trace(),
+            Err(MatrixError::NotSquare(_))
+        ));
+    }
+
+    #[test]
+    fn test_determinant_2x2() {
+        let arr = vec![vec![3.0, 8.0], vec![4.0, 6.0]];
+
+        assert!((-14.0 - MatrixRowMajor::new(&arr).determinant().unwrap()).abs() < 1e-9);
+        assert!((-14.0 - MatrixColMajor::new(&arr).determinant().unwrap()).abs() < 1e-9);
+        assert!((-14.0 - MatrixBlocky::new(&arr).determinant().unwrap()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_determinant_3x3() {
+        // the first pivot is zero so this also needs a row swap
+        let arr = vec![
+            vec![0.0, 2.0, 1.0],
+            vec![3.0, -1.0, 2.0],
+            vec![1.0, 4.0, -2.0],
+        ];
+
+        assert!((29.0 - MatrixRowMajor::new(&arr).determinant().unwrap()).abs() < 1e-9);
+        assert!((29.0 - MatrixColMajor::new(&arr).determinant().unwrap()).abs() < 1e-9);
+        assert!((29.0 - MatrixBlocky::new(&arr).determinant().unwrap()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_determinant_singular() {
+        let arr = vec![
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ];
+
+        assert!(MatrixRowMajor::new(&arr).determinant().unwrap().abs() < 1e-9);
+        assert!(matches!(
+            MatrixRowMajor::new(&[vec![1.0, 2.0]]).determinant(),
+            Err(MatrixError::NotSquare(_))
+        ));
+    }
+
+    #[test]
+    fn test_submatrix() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..4).map(|j| i * 4 + j + 1).collect())
+            .collect();
+        let top_left = vec![vec![1, 2], vec![5, 6]];
+        let middle = vec![vec![6, 7], vec![10, 11]];
+
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+        assert_matches_slice(&row.submatrix(0..2, 0..2).unwrap(), &top_left);
+        assert_matches_slice(&col.submatrix(0..2, 0..2).unwrap(), &top_left);
+        assert_matches_slice(&block.submatrix(0..2, 0..2).unwrap(), &top_left);
+        assert_matches_slice(&row.submatrix(1..3, 1..3).unwrap(), &middle);
+        assert_matches_slice(&col.submatrix(1..3, 1..3).unwrap(), &middle);
+        assert_matches_slice(&block.submatrix(1..3, 1..3).unwrap(), &middle);
+    }
+
+    #[test]
+    fn test_submatrix_out_of_range() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..4).map(|j| i * 4 + j + 1).collect())
+            .collect();
+
+        assert!(MatrixRowMajor::new(&arr).submatrix(2..5, 0..2).is_err());
+        assert!(MatrixColMajor::new(&arr).submatrix(0..2, 3..5).is_err());
+        assert!(MatrixBlocky::new(&arr).submatrix(0..2, 3..5).is_err());
+        assert!(matches!(
+            MatrixRowMajor::new(&arr).submatrix(2..5, 0..2),
+            Err(MatrixError::InvalidRange {
+                start: 2,
+                end: 5,
+                len: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_swap_rows() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let swapped = vec![vec![7, 8, 9], vec![4, 5, 6], vec![1, 2, 3]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        row.swap_rows(0, 2).unwrap();
+        col.swap_rows(2, 0).unwrap();
+        block.swap_rows(0, 2).unwrap();
+        assert_matches_slice(&row, &swapped);
+        assert_matches_slice(&col, &swapped);
+        assert_matches_slice(&block, &swapped);
+    }
+
+    #[test]
+    fn test_swap_cols() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let swapped = vec![vec![2, 1, 3], vec![5, 4, 6], vec![8, 7, 9]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        row.swap_cols(0, 1).unwrap();
+        col.swap_cols(1, 0).unwrap();
+        block.swap_cols(0, 1).unwrap();
+        assert_matches_slice(&row, &swapped);
+        assert_matches_slice(&col, &swapped);
+        assert_matches_slice(&block, &swapped);
+    }
+
+    #[test]
+    fn test_swap_same_index_and_out_of_range() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        row.swap_rows(1, 1).unwrap();
+        col.swap_rows(1, 1).unwrap();
+        block.swap_rows(1, 1).unwrap();
+        assert_matches_slice(&row, &arr);
+        assert_matches_slice(&col, &arr);
+        assert_matches_slice(&block, &arr);
+        assert!(row.swap_rows(0, 3).is_err());
+        assert!(col.swap_cols(3, 0).is_err());
+        assert!(block.swap_rows(3, 0).is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let row: MatrixRowMajor<i32> = "1 2 3\n4 5 6".parse().unwrap();
+
+        assert_matches_slice(&row, &[vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+
+    #[test]
+    fn test_from_str_ragged() {
+        assert!(matches!(
+            "1 2 3\n4 5".parse::<MatrixRowMajor<i32>>(),
+            Err(MatrixError::Jagged(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_str_invalid_token() {
+        match "1 2 3\n4 x 6".parse::<MatrixRowMajor<i32>>() {
+            Err(MatrixError::ParseError(msg)) => assert!(msg.contains("\"x\""), "{}", msg),
+            Err(other) => panic!("expected ParseError, got {:?}", other),
+            Ok(_) => panic!("expected ParseError"),
+        }
+    }
+
+    #[test]
+    fn test_map_to_f64() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let row = MatrixRowMajor::new(&arr).map(|x| x as f64 * 2.0);
+        let col = MatrixColMajor::new(&arr).map(|x| x as f64 * 2.0);
+        let block = MatrixBlocky::new(&arr).map(|x| x as f64 * 2.0);
+
+        for (i, line) in arr.iter().enumerate() {
+            for (j, &x) in line.iter().enumerate() {
+                assert_eq!(x as f64 * 2.0, row.get(i, j).unwrap());
+                assert_eq!(x as f64 * 2.0, col.get(i, j).unwrap());
+                assert_eq!(x as f64 * 2.0, block.get(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_to_other_type() {
+        let arr = vec![vec![1, -2, 3], vec![-4, 5, -6], vec![7, -8, 9]];
+        let expected = [
+            [true, false, true],
+            [false, true, false],
+            [true, false, true],
+        ];
+
+        let row = MatrixRowMajor::new(&arr).map(|x| x > 0);
+        let col = MatrixColMajor::new(&arr).map(|x| x > 0);
+        let block = MatrixBlocky::new(&arr).map(|x| x > 0);
+        for (i, line) in expected.iter().enumerate() {
+            for (j, &positive) in line.iter().enumerate() {
+                assert_eq!(positive, row.get(i, j).unwrap());
+                assert_eq!(positive, col.get(i, j).unwrap());
+                assert_eq!(positive, block.get(i, j).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_string_matrix() {
+        let arr = vec![
+            vec!["a".to_string(), "b".to_string()],
+            vec!["c".to_string(), "d".to_string()],
+        ];
+        let mut row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!("b", row.get(0, 1).unwrap());
+        assert_eq!("c", col.get(1, 0).unwrap());
+        assert_eq!("d", block.get(1, 1).unwrap());
+        row.set(0, 0, "z".to_string()).unwrap();
+        assert_eq!(vec!["z".to_string(), "b".to_string()], row.row(0).unwrap());
+        assert_eq!("z b\nc d", row.to_string());
+        assert!(col == block);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_par_mul_matches_mul() {
+        let lhs = MatrixRowMajor::from_fn(64, 64, |i, j| (i * 7 + j * 3) as i64 % 11 - 5);
+        let rhs = MatrixRowMajor::from_fn(64, 64, |i, j| (i * 5 + j) as i64 % 13 - 6);
+
+        let serial = lhs.mul(&rhs).unwrap();
+        let parallel = lhs.par_mul(&rhs).unwrap();
+        assert_eq!(serial.arr, parallel.arr);
+        assert_eq!((64, 64), (parallel.rows(), parallel.cols()));
+        assert!(matches!(
+            lhs.par_mul(&MatrixRowMajor::zeros(3, 64)),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_block_mul_matches_row_mul() {
+        let lhs: Vec<Vec<i32>> = (0..8)
+            .map(|i| (0..8).map(|j| (i * 3 + j * 5) % 7 - 3).collect())
+            .collect();
+        let rhs: Vec<Vec<i32>> = (0..8)
+            .map(|i| (0..8).map(|j| (i * 2 + j) % 5 - 2).collect())
+            .collect();
+        let odd: Vec<Vec<i32>> = (0..8)
+            .map(|i| (0..5).map(|j| i * 5 + j).collect())
+            .collect();
+
+        let row = MatrixRowMajor::new(&lhs);
+        let block = MatrixBlocky::new(&lhs);
+        assert!(
+            block.mul(&MatrixBlocky::new(&rhs)).unwrap()
+                == row.mul(&MatrixRowMajor::new(&rhs)).unwrap()
+        );
+        assert!(
+            block.mul(&MatrixBlocky::new(&odd)).unwrap()
+                == row.mul(&MatrixRowMajor::new(&odd)).unwrap()
+        );
+    }
+
+    fn square(n: i32) -> Vec<Vec<i32>> {
+        (0..n)
+            .map(|i| (0..n).map(|j| i * n + j + 1).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_block_size_2_on_6x6() {
+        let arr = square(6);
+        let block = MatrixBlocky::with_block_size(&arr, 2);
+
+        assert_eq!(9, block.arr.len());
+        assert!(block.arr.iter().all(|b| b.rows == 2 && b.cols == 2));
+        assert_matches_slice(&block, &arr);
+        assert_eq!(arr[3], block.row(3).unwrap());
+        assert_eq!(vec![5, 11, 17, 23, 29, 35], block.col(4).unwrap());
+        assert_eq!(arr.concat(), block.iter_row_major().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_block_size_3_on_6x6() {
+        let arr = square(6);
+        let mut block = MatrixBlocky::with_block_size(&arr, 3);
+
+        assert_eq!(4, block.arr.len());
+        assert_matches_slice(&block, &arr);
+        block.set(4, 2, 0).unwrap();
+        assert_eq!(0, block.arr[2].get(1, 2).unwrap());
+    }
+
+    #[test]
+    fn test_block_size_2_on_5x5() {
+        let arr = square(5);
+        let block = MatrixBlocky::with_block_size(&arr, 2);
+
+        assert_eq!(9, block.arr.len());
+        let shapes: Vec<_> = block.arr.iter().map(|b| (b.rows, b.cols)).collect();
+        assert_eq!((2, 1), shapes[2]);
+        assert_eq!((1, 2), shapes[6]);
+        assert_eq!((1, 1), shapes[8]);
+        assert_matches_slice(&block, &arr);
+        assert_eq!(vec![5, 10, 15, 20, 25], block.col(4).unwrap());
+
+        let quarters = MatrixBlocky::new(&arr);
+        let product = block.mul(&block).unwrap();
+        assert!(quarters.mul(&quarters).unwrap() == product);
+        assert!(product == block.mul(&quarters).unwrap());
+        assert!(block.add(&block).unwrap() == block.add(&quarters).unwrap());
+    }
+
+    #[test]
+    fn test_new_fills_storage_in_layout_order() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+
+        assert_eq!(vec![1, 2, 3, 4, 5, 6], row.arr);
+        assert_eq!(vec![1, 4, 2, 5, 3, 6], col.arr);
+    }
+
+    #[test]
+    fn test_shape_queries() {
+        let square = MatrixRowMajor::new(&[vec![1, 2], vec![3, 4]]);
+        assert_eq!((2, 2), square.shape());
+        assert!(square.is_square());
+        assert!(!square.is_empty());
+
+        let rect = MatrixColMajor::new(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!((2, 3), rect.shape());
+        assert!(!rect.is_square());
+        assert!(!rect.is_empty());
+
+        let empty = <MatrixBlocky<i32> as Matrix<i32>>::default();
+        assert_eq!((0, 0), empty.shape());
+        assert!(empty.is_square());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let square = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let wide = [vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 10, 11, 12]];
+
+        assert_eq!(vec![1, 5, 9], MatrixRowMajor::new(&square).diagonal());
+        assert_eq!(vec![1, 5, 9], MatrixColMajor::new(&square).diagonal());
+        assert_eq!(vec![1, 6, 11], MatrixRowMajor::new(&wide).diagonal());
+        assert_eq!(vec![1, 6, 11], MatrixColMajor::new(&wide).diagonal());
+        assert_eq!(vec![1, 6, 11], MatrixBlocky::new(&wide).diagonal());
+    }
+
+    #[test]
+    fn test_iter_rows() {
+        let arr = vec![
+            vec![1, 2, 3, 4], //
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(arr, row.iter_rows().collect::<Vec<_>>());
+        assert_eq!(arr, col.iter_rows().collect::<Vec<_>>());
+        assert_eq!(3, block.iter_rows().len());
+        let mut rows = Vec::new();
+        for r in &block {
+            rows.push(r);
+        }
+        assert_eq!(arr, rows);
+    }
+
+    #[test]
+    fn test_error_as_boxed_error() {
+        fn first_row() -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+            let row = MatrixRowMajor::try_new(&[vec![1, 2], vec![3]])?;
+            Ok(row.row(0)?)
+        }
+
+        let err = first_row().unwrap_err();
+        assert_eq!(
+            "Jagged rows: row 0 has 2 elements but row 1 has 1",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_try_get() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(Some(6), row.try_get(1, 2));
+        assert_eq!(Some(2), col.try_get(0, 1));
+        assert_eq!(Some(4), block.try_get(1, 0));
+        assert_eq!(None, row.try_get(0, 3));
+        assert_eq!(None, col.try_get(2, 0));
+        assert_eq!(None, block.try_get(2, 3));
+    }
+
+    #[test]
+    fn test_fill() {
+        let arr: Vec<Vec<i32>> = (0..3)
+            .map(|i| (0..3).map(|j| i * 3 + j + 1).collect())
+            .collect();
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        row.fill(7);
+        col.fill(7);
+        block.fill(7);
+        let sevens = vec![vec![7; 3]; 3];
+        assert_matches_slice(&row, &sevens);
+        assert_matches_slice(&col, &sevens);
+        assert_matches_slice(&block, &sevens);
+    }
+
+    #[test]
+    fn test_resize_grow() {
+        let arr = [vec![1, 2], vec![3, 4]];
+        let grown = [vec![1, 2, 0], vec![3, 4, 0], vec![0, 0, 0]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        row.resize(3, 3, 0);
+        col.resize(3, 3, 0);
+        block.resize(3, 3, 0);
+        assert_matches_slice(&row, &grown);
+        assert_matches_slice(&col, &grown);
+        assert_matches_slice(&block, &grown);
+
+        row.resize(4, 3, 9);
+        assert_eq!(vec![9, 9, 9], row.row(3).unwrap());
+    }
+
+    #[test]
+    fn test_resize_shrink() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let corner = [vec![1, 2], vec![4, 5]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        row.resize(2, 2, 0);
+        col.resize(2, 2, 0);
+        block.resize(2, 2, 0);
+        assert_matches_slice(&row, &corner);
+        assert_matches_slice(&col, &corner);
+        assert_matches_slice(&block, &corner);
+
+        col.resize(2, 1, 0);
+        assert_matches_slice(&col, &[vec![1], vec![4]]);
+    }
+
+    #[test]
+    fn test_hadamard() {
+        let a = [vec![1, 2, 3], vec![4, 5, 6]];
+        let b = [vec![2, 0, 1], vec![-1, 3, 2]];
+        let product = [vec![2, 0, 3], vec![-4, 15, 12]];
+
+        let row = MatrixRowMajor::new(&a).hadamard(&MatrixRowMajor::new(&b));
+        let col = MatrixColMajor::new(&a).hadamard(&MatrixColMajor::new(&b));
+        let block = MatrixBlocky::new(&a).hadamard(&MatrixBlocky::new(&b));
+        assert_matches_slice(&row.unwrap(), &product);
+        assert_matches_slice(&col.unwrap(), &product);
+        assert_matches_slice(&block.unwrap(), &product);
+
+        let square = MatrixRowMajor::new(&[vec![1, 2], vec![3, 4]]);
+        assert!(matches!(
+            MatrixRowMajor::new(&a).hadamard(&square),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_pow() {
+        let arr = [vec![1, 1], vec![1, 0]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert!(row.pow(0).unwrap() == MatrixRowMajor::identity(2));
+        assert!(row.pow(1).unwrap() == row);
+        assert_matches_slice(&row.pow(3).unwrap(), &[vec![3, 2], vec![2, 1]]);
+        assert_matches_slice(&col.pow(3).unwrap(), &[vec![3, 2], vec![2, 1]]);
+        assert_matches_slice(&block.pow(10).unwrap(), &[vec![89, 55], vec![55, 34]]);
+
+        let rect = MatrixRowMajor::new(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        assert!(matches!(rect.pow(2), Err(MatrixError::NotSquare(_))));
+    }
+
+    #[test]
+    fn test_dot() {
+        let a = MatrixRowMajor::new(&[vec![1, 2, 3]]);
+        let b = MatrixRowMajor::new(&[vec![4, -5, 6]]);
+        assert_eq!(12, a.dot(&b).unwrap());
+
+        let c = MatrixColMajor::new(&[vec![1], vec![2], vec![3]]);
+        let d = MatrixColMajor::new(&[vec![2], vec![2], vec![2]]);
+        assert_eq!(12, c.dot(&d).unwrap());
+
+        let short = MatrixRowMajor::new(&[vec![1, 2]]);
+        let square = MatrixRowMajor::new(&[vec![1, 2], vec![3, 4]]);
+        assert!(matches!(
+            a.dot(&short),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            square.dot(&square),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_norms() {
+        let arr = [vec![3.0, 4.0], vec![0.0, 12.0]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+
+        assert_eq!(5.0, row.row_norm(0).unwrap());
+        assert_eq!(12.0, col.row_norm(1).unwrap());
+        assert_eq!(3.0, row.col_norm(0).unwrap());
+        assert!((col.col_norm(1).unwrap() - 160f64.sqrt()).abs() < 1e-12);
+        assert!(row.row_norm(2).is_err());
+        assert!(col.col_norm(2).is_err());
+    }
+
+    #[test]
+    fn test_row_echelon() {
+        let arr = [
+            vec![2.0, 1.0, -1.0],
+            vec![-3.0, -1.0, 2.0],
+            vec![-2.0, 1.0, 2.0],
+        ];
+        let echelon = [
+            vec![-3.0, -1.0, 2.0],
+            vec![0.0, 5.0 / 3.0, 2.0 / 3.0],
+            vec![0.0, 0.0, 0.2],
+        ];
+
+        assert_close(&MatrixRowMajor::new(&arr).row_echelon(), &echelon);
+        assert_close(&MatrixColMajor::new(&arr).row_echelon(), &echelon);
+        assert_close(&MatrixBlocky::new(&arr).row_echelon(), &echelon);
+    }
+
+    #[test]
+    fn test_row_echelon_zero_pivot() {
+        let arr = [vec![0.0, 2.0, 4.0], vec![1.0, 3.0, 5.0]];
+        assert_close(
+            &MatrixRowMajor::new(&arr).row_echelon(),
+            &[vec![1.0, 3.0, 5.0], vec![0.0, 2.0, 4.0]],
+        );
+
+        let arr = [vec![0.0, 1.0], vec![0.0, 2.0]];
+        assert_close(
+            &MatrixColMajor::new(&arr).row_echelon(),
+            &[vec![0.0, 2.0], vec![0.0, 0.0]],
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        let full = [
+            vec![2.0, 0.0, 1.0],
+            vec![1.0, 3.0, 2.0],
+            vec![1.0, 1.0, 2.0],
+        ];
+        let dependent = [
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ];
+        let wide = [vec![1.0, 2.0, 3.0, 4.0], vec![2.0, 4.0, 6.0, 8.0]];
+
+        assert_eq!(3, MatrixRowMajor::new(&full).rank());
+        assert_eq!(2, MatrixRowMajor::new(&dependent).rank());
+        assert_eq!(2, MatrixColMajor::new(&dependent).rank());
+        assert_eq!(1, MatrixBlocky::new(&wide).rank());
+        assert_eq!(0, MatrixRowMajor::<f64>::zeros(3, 2).rank());
+    }
+
+    #[test]
+    fn test_inverse_2x2() {
+        let arr = [vec![4.0, 7.0], vec![2.0, 6.0]];
+        let row = MatrixRowMajor::new(&arr);
+        let inverse = row.inverse().unwrap();
+
+        assert_close(&inverse, &[vec![0.6, -0.7], vec![-0.2, 0.4]]);
+        assert_close(
+            &row.mul(&inverse).unwrap(),
+            &[vec![1.0, 0.0], vec![0.0, 1.0]],
+        );
+    }
+
+    #[test]
+    fn test_inverse_3x3() {
+        let arr = [
+            vec![0.0, 2.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+            vec![3.0, 0.0, 2.0],
+        ];
+        let identity: Vec<Vec<f64>> = (0..3)
+            .map(|i| (0..3).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+        assert_close(&row.mul(&row.inverse().unwrap()).unwrap(), &identity);
+        assert_close(&col.mul(&col.inverse().unwrap()).unwrap(), &identity);
+        assert_close(&block.inverse().unwrap().mul(&block).unwrap(), &identity);
+    }
+
+    #[test]
+    fn test_inverse_singular() {
+        let arr = [
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ];
+
+        assert!(matches!(
+            MatrixRowMajor::new(&arr).inverse(),
+            Err(MatrixError::Singular(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::new(&[vec![1.0, 2.0]]).inverse(),
+            Err(MatrixError::NotSquare(_))
+        ));
+    }
+
+    #[test]
+    fn test_solve() {
+        // x = 1, y = -2, z = 3
+        let a = [
+            vec![2.0, 1.0, -1.0],
+            vec![-3.0, -1.0, 2.0],
+            vec![-2.0, 1.0, 2.0],
+        ];
+        let b = [vec![-3.0], vec![5.0], vec![2.0]];
+        let x = [vec![1.0], vec![-2.0], vec![3.0]];
+
+        let row = MatrixRowMajor::new(&a).solve(&MatrixRowMajor::new(&b));
+        let col = MatrixColMajor::new(&a).solve(&MatrixColMajor::new(&b));
+        let block = MatrixBlocky::new(&a).solve(&MatrixBlocky::new(&b));
+        assert_close(&row.unwrap(), &x);
+        assert_close(&col.unwrap(), &x);
+        assert_close(&block.unwrap(), &x);
+
+        let singular = MatrixRowMajor::new(&[vec![1.0, 2.0], vec![2.0, 4.0]]);
+        assert!(matches!(
+            singular.solve(&MatrixRowMajor::new(&[vec![1.0], vec![2.0]])),
+            Err(MatrixError::Singular(_))
+        ));
+        assert!(matches!(
+            singular.solve(&MatrixRowMajor::new(&[vec![1.0, 2.0]])),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_kronecker() {
+        let a = [vec![1, 2], vec![3, 4]];
+        let b = [vec![0, 5], vec![6, 7]];
+        let product = [
+            vec![0, 5, 0, 10],
+            vec![6, 7, 12, 14],
+            vec![0, 15, 0, 20],
+            vec![18, 21, 24, 28],
+        ];
+
+        let row = MatrixRowMajor::new(&a).kronecker(&MatrixRowMajor::new(&b));
+        let col = MatrixColMajor::new(&a).kronecker(&MatrixColMajor::new(&b));
+        let block = MatrixBlocky::new(&a).kronecker(&MatrixBlocky::new(&b));
+        assert_matches_slice(&row, &product);
+        assert_matches_slice(&col, &product);
+        assert_matches_slice(&block, &product);
+
+        let wide = MatrixRowMajor::new(&[vec![1, 2, 3]]);
+        assert_eq!((2, 6), MatrixRowMajor::new(&a).kronecker(&wide).shape());
+    }
+
+    #[test]
+    fn test_hstack_vstack() {
+        let a = [vec![1, 2], vec![3, 4]];
+        let b = [vec![5, 6], vec![7, 8]];
+        let side = [vec![1, 2, 5, 6], vec![3, 4, 7, 8]];
+        let stacked = [vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8]];
+
+        let (ra, rb) = (MatrixRowMajor::new(&a), MatrixRowMajor::new(&b));
+        let (ca, cb) = (MatrixColMajor::new(&a), MatrixColMajor::new(&b));
+        let (ba, bb) = (MatrixBlocky::new(&a), MatrixBlocky::new(&b));
+        assert_matches_slice(&ra.hstack(&rb).unwrap(), &side);
+        assert_matches_slice(&ca.hstack(&cb).unwrap(), &side);
+        assert_matches_slice(&ba.hstack(&bb).unwrap(), &side);
+        assert_matches_slice(&ra.vstack(&rb).unwrap(), &stacked);
+        assert_matches_slice(&ca.vstack(&cb).unwrap(), &stacked);
+        assert_matches_slice(&ba.vstack(&bb).unwrap(), &stacked);
+    }
+
+    #[test]
+    fn test_stack_mismatch() {
+        let square = MatrixRowMajor::new(&[vec![1, 2], vec![3, 4]]);
+        let wide = MatrixRowMajor::new(&[vec![1, 2, 3]]);
+
+        assert!(matches!(
+            square.hstack(&wide),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            square.vstack(&wide),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert_eq!(
+            (3, 2),
+            square
+                .vstack(&MatrixRowMajor::new(&[vec![5, 6]]))
+                .unwrap()
+                .shape()
+        );
+    }
+
+    #[test]
+    fn test_reshape() {
+        let arr: Vec<Vec<i32>> = (0..2)
+            .map(|i| (0..6).map(|j| i * 6 + j + 1).collect())
+            .collect();
+        let three = [vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 10, 11, 12]];
+        let four = [
+            vec![1, 2, 3],
+            vec![4, 5, 6],
+            vec![7, 8, 9],
+            vec![10, 11, 12],
+        ];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_matches_slice(&row.reshape(3, 4).unwrap(), &three);
+        assert_matches_slice(&col.reshape(3, 4).unwrap(), &three);
+        assert_matches_slice(&block.reshape(3, 4).unwrap(), &three);
+        assert_matches_slice(&row.reshape(4, 3).unwrap(), &four);
+        assert_matches_slice(&col.reshape(4, 3).unwrap(), &four);
+        assert_matches_slice(&block.reshape(4, 3).unwrap(), &four);
+        assert!(matches!(
+            col.reshape(5, 2),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let doubled = [vec![2, 4, 6], vec![8, 10, 12], vec![14, 16, 18]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::with_block_size(&arr, 2);
+
+        row.apply(|x| *x *= 2);
+        col.apply(|x| *x *= 2);
+        block.apply(|x| *x *= 2);
+        assert_matches_slice(&row, &doubled);
+        assert_matches_slice(&col, &doubled);
+        assert_matches_slice(&block, &doubled);
+    }
+
+    #[test]
+    fn test_from_flat() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+        let row = MatrixRowMajor::from_flat(vec![1, 2, 3, 4, 5, 6], 2, 3).unwrap();
+        let col = MatrixColMajor::from_flat(vec![1, 4, 2, 5, 3, 6], 2, 3).unwrap();
+
+        assert_matches_slice(&row, &arr);
+        assert_matches_slice(&col, &arr);
+        assert!(matches!(
+            MatrixRowMajor::from_flat(vec![1, 2, 3, 4, 5], 2, 3),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::from_flat(vec![1, 2, 3, 4, 5, 6, 7], 2, 3),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = MatrixRowMajor::new(&[vec![1.0, 2.0], vec![3.0, 4.0]]);
+        let close = MatrixRowMajor::new(&[vec![1.0 + 1e-10, 2.0], vec![3.0, 4.0 - 1e-10]]);
+        let far = MatrixRowMajor::new(&[vec![1.0, 2.0], vec![3.0, 4.5]]);
+        let wide = MatrixRowMajor::new(&[vec![1.0, 2.0, 3.0, 4.0]]);
+
+        assert!(a.approx_eq(&close, 1e-9));
+        assert!(!a.approx_eq(&far, 1e-9));
+        assert!(!a.approx_eq(&wide, 1.0));
+
+        let inverse = MatrixColMajor::new(&[vec![4.0, 7.0], vec![2.0, 6.0]])
+            .inverse()
+            .unwrap();
+        let expected = MatrixColMajor::new(&[vec![0.6, -0.7], vec![-0.2, 0.4]]);
+        assert!(inverse.approx_eq(&expected, 1e-12));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let mut row = MatrixRowMajor::new(&arr);
+        let mut col = MatrixColMajor::new(&arr);
+        let mut block = MatrixBlocky::new(&arr);
+
+        *row.get_mut(0, 2).unwrap() += 10;
+        *col.get_mut(2, 0).unwrap() += 10;
+        let cell = block.get_mut(2, 2).unwrap();
+        assert_eq!(9, *cell);
+        *cell = 0;
+
+        assert_eq!(13, row.get(0, 2).unwrap());
+        assert_eq!(17, col.get(2, 0).unwrap());
+        assert_eq!(0, block.get(2, 2).unwrap());
+        assert!(matches!(
+            block.get_mut(3, 0),
+            Err(MatrixError::OutOfBoundIndexing { .. })
+        ));
+    }
+
+    #[test]
+    fn test_const_matrix() {
+        let mut a = MatrixConst::<i32, 2, 3>::new([[1, 2, 3], [4, 5, 6]]);
+        let b = MatrixConst::new([[1, 0], [0, 1], [2, 3]]);
+
+        assert_eq!((2, 3), (a.rows(), a.cols()));
+        assert_eq!(6, a.get(1, 2).unwrap());
+        assert!(matches!(
+            a.get(0, 3),
+            Err(MatrixError::ForbiddenIndexing { .. })
+        ));
+        assert!(matches!(
+            a.set(2, 0, 0),
+            Err(MatrixError::OutOfBoundIndexing { .. })
+        ));
+        a.set(0, 0, 7).unwrap();
+        assert_eq!(7, a[(0, 0)]);
+
+        let product: MatrixConst<i32, 2, 2> = a.mul(&b);
+        assert_eq!(MatrixConst::new([[13, 11], [16, 23]]), product);
+        assert_eq!(MatrixConst::new([[7, 4], [2, 5], [3, 6]]), a.transpose());
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let a = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let b = [vec![9, 8, 7], vec![6, 5, 4], vec![3, 2, 1]];
+
+        let (mut row, rb) = (MatrixRowMajor::new(&a), MatrixRowMajor::new(&b));
+        let sum = row.add(&rb).unwrap();
+        row += &rb;
+        assert!(row == sum);
+        let difference = row.sub(&rb).unwrap();
+        row -= &rb;
+        assert!(row == difference);
+        let scaled = row.scalar_mul(3);
+        row *= 3;
+        assert!(row == scaled);
+
+        let (mut col, cb) = (MatrixColMajor::new(&a), MatrixColMajor::new(&b));
+        col += &cb;
+        col *= 2;
+        assert!(col == rb.add(&MatrixRowMajor::new(&a)).unwrap().scalar_mul(2));
+
+        let mut block = MatrixBlocky::new(&a);
+        block -= &MatrixBlocky::with_block_size(&b, 2);
+        assert!(block == MatrixRowMajor::new(&a).sub(&rb).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn test_add_assign_mismatch() {
+        let mut row = MatrixRowMajor::new(&[vec![1, 2]]);
+        row += &MatrixRowMajor::new(&[vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_neg() {
+        let arr = [vec![1, -2], vec![-3, 4]];
+        let negated = [vec![-1, 2], vec![3, -4]];
+
+        assert_matches_slice(&-MatrixRowMajor::new(&arr), &negated);
+        assert_matches_slice(&-&MatrixColMajor::new(&arr), &negated);
+        assert_matches_slice(&-MatrixBlocky::new(&arr), &negated);
+        assert_matches_slice(&-&MatrixBlocky::new(&arr), &negated);
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let arr = vec![vec![1, 2, 3], vec![4, 5, 6]];
+
+        assert_matches_slice(&MatrixRowMajor::from_rows(arr.clone()).unwrap(), &arr);
+        assert_matches_slice(&MatrixColMajor::from_rows(arr.clone()).unwrap(), &arr);
+        assert_matches_slice(&MatrixBlocky::from_rows(arr.clone()).unwrap(), &arr);
+
+        let lazy = (0..2).map(|i| (1..4).map(|j| i * 3 + j).collect::<Vec<i32>>());
+        assert_matches_slice(&MatrixRowMajor::from_rows(lazy.clone()).unwrap(), &arr);
+        assert_matches_slice(&MatrixColMajor::from_rows(lazy).unwrap(), &arr);
+
+        let jagged = vec![vec![1, 2], vec![3, 4], vec![5]];
+        assert!(matches!(
+            MatrixRowMajor::from_rows(jagged.clone()),
+            Err(MatrixError::Jagged(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::from_rows(jagged),
+            Err(MatrixError::Jagged(_))
+        ));
+    }
+
+    #[test]
+    fn test_as_slice() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+
+        assert_eq!(&[1, 2, 3, 4, 5, 6], MatrixRowMajor::new(&arr).as_slice());
+        assert_eq!(&[1, 4, 2, 5, 3, 6], MatrixColMajor::new(&arr).as_slice());
+        assert_eq!(None, MatrixBlocky::new(&arr).as_slice());
+        assert_eq!(
+            Some(&[1, 2, 3, 4, 5, 6][..]),
+            MatrixBlocky::with_block_size(&arr, 3).as_slice()
+        );
+    }
+
+    #[test]
+    fn test_reductions() {
+        let arr = [vec![4, -2, 7], vec![0, 9, 1], vec![3, -5, 6]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(23, row.sum());
+        assert_eq!(23, col.sum());
+        assert_eq!(23, block.sum());
+        assert_eq!(Some(-5), col.min());
+        assert_eq!(Some(9), block.max());
+
+        let empty = <MatrixRowMajor<f64> as Matrix<f64>>::default();
+        assert_eq!(0.0, empty.sum());
+        assert_eq!(None, empty.min());
+        assert_eq!(None, empty.max());
+    }
+
+    #[test]
+    fn test_row_col_sums() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        assert_eq!(vec![6, 15], row.row_sums());
+        assert_eq!(vec![6, 15], col.row_sums());
+        assert_eq!(vec![6, 15], block.row_sums());
+        assert_eq!(vec![5, 7, 9], row.col_sums());
+        assert_eq!(vec![5, 7, 9], col.col_sums());
+        assert_eq!(vec![5, 7, 9], block.col_sums());
+    }
+
+    #[test]
+    fn test_is_symmetric() {
+        let symmetric = [vec![1, 2, 3], vec![2, 5, 6], vec![3, 6, 9]];
+        let skewed = [vec![1, 2, 3], vec![2, 5, 6], vec![3, 7, 9]];
+
+        assert!(MatrixRowMajor::new(&symmetric).is_symmetric());
+        assert!(MatrixColMajor::new(&symmetric).is_symmetric());
+        assert!(MatrixBlocky::new(&symmetric).is_symmetric());
+        assert!(!MatrixRowMajor::new(&skewed).is_symmetric());
+        assert!(!MatrixRowMajor::new(&[vec![1, 1, 1], vec![1, 1, 1]]).is_symmetric());
+
+        let nearly = MatrixRowMajor::new(&[vec![1.0, 2.0], vec![2.0 + 1e-12, 3.0]]);
+        assert!(!nearly.is_symmetric());
+        assert!(nearly.is_symmetric_within(1e-9));
+        assert!(!MatrixColMajor::new(&[vec![1.0, 2.0]]).is_symmetric_within(1e-9));
+    }
+
+    #[test]
+    fn test_cholesky() {
+        let arr = [
+            vec![4.0, 12.0, -16.0],
+            vec![12.0, 37.0, -43.0],
+            vec![-16.0, -43.0, 98.0],
+        ];
+        let factor = [
+            vec![2.0, 0.0, 0.0],
+            vec![6.0, 1.0, 0.0],
+            vec![-8.0, 5.0, 3.0],
+        ];
+
+        let row = MatrixRowMajor::new(&arr);
+        let l = row.cholesky().unwrap();
+        assert_close(&l, &factor);
+        assert_close(&l.mul(&l.transposed()).unwrap(), &arr);
+        let l = MatrixBlocky::new(&arr).cholesky().unwrap();
+        assert_close(&l.mul(&l.transposed()).unwrap(), &arr);
+
+        let indefinite = MatrixColMajor::new(&[vec![1.0, 2.0], vec![2.0, 1.0]]);
+        assert!(matches!(
+            indefinite.cholesky(),
+            Err(MatrixError::NotPositiveDefinite(_))
+        ));
+
+        let asymmetric = MatrixRowMajor::new(&[vec![4.0, 1.0], vec![3.0, 4.0]]);
+        assert!(matches!(
+            asymmetric.cholesky(),
+            Err(MatrixError::NotPositiveDefinite(_))
+        ));
+        let nan = MatrixRowMajor::new(&[vec![f64::NAN, 0.0], vec![0.0, 1.0]]);
+        assert!(matches!(
+            nan.cholesky(),
+            Err(MatrixError::NotPositiveDefinite(_))
+        ));
+    }
+
+    #[test]
+    fn test_lu() {
+        let arr = [
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 10.0],
+        ];
+        let a = MatrixRowMajor::new(&arr);
+        let (l, u, perm) = a.lu().unwrap();
+
+        for i in 0..3 {
+            assert_eq!(1.0, l.get(i, i).unwrap());
+            for j in i + 1..3 {
+                assert_eq!(0.0, l.get(i, j).unwrap());
+                assert_eq!(0.0, u.get(j, i).unwrap());
+            }
+        }
+        let permuted: Vec<Vec<f64>> = perm.iter().map(|&i| arr[i].clone()).collect();
+        assert_eq!(vec![2, 0, 1], perm);
+        assert_close(&l.mul(&u).unwrap(), &permuted);
+
+        let (l, u, perm) = MatrixColMajor::new(&arr).lu().unwrap();
+        let permuted: Vec<Vec<f64>> = perm.iter().map(|&i| arr[i].clone()).collect();
+        assert_close(&l.mul(&u).unwrap(), &permuted);
+        assert!(matches!(
+            MatrixRowMajor::new(&[vec![1.0, 2.0]]).lu(),
+            Err(MatrixError::NotSquare(_))
+        ));
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let shifted = [vec![11, 22, 33], vec![14, 25, 36], vec![17, 28, 39]];
+
+        let row = MatrixRowMajor::new(&arr);
+        let v = MatrixRowMajor::new(&[vec![10, 20, 30]]);
+        assert_matches_slice(&row.add_row_vector(&v).unwrap(), &shifted);
+        let block = MatrixBlocky::new(&arr);
+        let v = MatrixBlocky::new(&[vec![10, 20, 30]]);
+        assert_matches_slice(&block.add_row_vector(&v).unwrap(), &shifted);
+
+        let col = MatrixColMajor::new(&arr);
+        let v = MatrixColMajor::new(&[vec![1], vec![0], vec![-1]]);
+        assert_matches_slice(
+            &col.add_col_vector(&v).unwrap(),
+            &[vec![2, 3, 4], vec![4, 5, 6], vec![6, 7, 8]],
+        );
+        assert!(matches!(
+            col.add_row_vector(&v),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            row.add_col_vector(&MatrixRowMajor::new(&[vec![1], vec![2]])),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_checked_overflow() {
+        let big = MatrixRowMajor::<i8>::new(&[vec![100, 20], vec![10, 1]]);
+        assert_eq!(None, big.checked_sum());
+        assert!(matches!(
+            big.checked_mul(&big),
+            Err(MatrixError::Overflow(_))
+        ));
+
+        let small = MatrixColMajor::<i8>::new(&[vec![1, 2], vec![3, 4]]);
+        assert_eq!(Some(10), small.checked_sum());
+        let product = MatrixColMajor::new(&[vec![7, 10], vec![15, 22]]);
+        assert!(small.checked_mul(&small).unwrap() == product);
+    }
+
+    #[test]
+    fn test_sparse_identity() {
+        let arr: Vec<Vec<i32>> = (0..100)
+            .map(|i| (0..100).map(|j| if i == j { 1 } else { 0 }).collect())
+            .collect();
+        let mut sparse = MatrixSparse::new(&arr);
+
+        assert_eq!(100, sparse.stored());
+        assert_eq!(1, sparse.get(42, 42).unwrap());
+        assert_eq!(0, sparse.get(42, 43).unwrap());
+        assert!(matches!(
+            sparse.get(0, 100),
+            Err(MatrixError::ForbiddenIndexing { .. })
+        ));
+        assert!(sparse.get(100, 0).is_err());
+
+        sparse.set(0, 0, 0).unwrap();
+        sparse.set(0, 99, 5).unwrap();
+        assert_eq!(100, sparse.stored());
+        assert_eq!(0, sparse.get(0, 0).unwrap());
+        assert_eq!(5, sparse.get(0, 99).unwrap());
+
+        // reading through get_mut stores a zero but does not count as an element
+        assert_eq!(0, *sparse.get_mut(3, 4).unwrap());
+        assert_eq!(100, sparse.stored());
+    }
+
+    #[test]
+    fn test_sparse_operations() {
+        let a = [vec![1, 0, 2], vec![0, 0, 3]];
+        let b = [vec![0, 4, -2], vec![5, 0, 0]];
+        let (sa, sb) = (MatrixSparse::new(&a), MatrixSparse::new(&b));
+
+        assert_matches_slice(&sa, &a);
+        assert_matches_slice(&sa.add(&sb).unwrap(), &[vec![1, 4, 0], vec![5, 0, 3]]);
+        assert_eq!(4, sa.add(&sb).unwrap().stored());
+        assert_matches_slice(&sa.hadamard(&sb).unwrap(), &[vec![0, 0, -4], vec![0, 0, 0]]);
+        assert_matches_slice(&sa.transposed(), &[vec![1, 0], vec![0, 0], vec![2, 3]]);
+        let product = sa.mul(&MatrixSparse::new(&[vec![1], vec![1], vec![1]]));
+        assert_matches_slice(&product.unwrap(), &[vec![3], vec![3]]);
+
+        let mut swapped = sa.clone();
+        swapped.swap_rows(0, 1).unwrap();
+        swapped.swap_cols(0, 2).unwrap();
+        assert_matches_slice(&swapped, &[vec![3, 0, 0], vec![2, 0, 1]]);
+    }
+
+    #[test]
+    fn test_nonzeros() {
+        let arr = [vec![0, 3, 0], vec![1, 0, 0], vec![0, 0, -2]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let sparse = MatrixSparse::new(&arr);
+
+        assert_eq!(3, row.nnz());
+        assert_eq!(3, col.nnz());
+        assert_eq!(3, sparse.nnz());
+        assert_eq!(vec![(0, 1), (1, 0), (2, 2)], row.nonzero_indices());
+        assert_eq!(vec![(0, 1), (1, 0), (2, 2)], col.nonzero_indices());
+        assert_eq!(0, MatrixBlocky::<i32>::zeros(2, 2).nnz());
+    }
+
+    #[test]
+    fn test_matvec() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+        let v = [1, 0, -1];
+
+        assert_eq!(vec![-2, -2], MatrixRowMajor::new(&arr).matvec(&v).unwrap());
+        assert_eq!(vec![-2, -2], MatrixColMajor::new(&arr).matvec(&v).unwrap());
+        assert_eq!(vec![-2, -2], MatrixBlocky::new(&arr).matvec(&v).unwrap());
+        assert!(matches!(
+            MatrixRowMajor::new(&arr).matvec(&[1, 2]),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            MatrixColMajor::new(&arr).matvec(&[1, 2, 3, 4]),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_with() {
+        let arr = [vec![1.0, -0.5], vec![1.0 / 3.0, 12.25]];
+        let expected = " 1.00  -0.50\n 0.33  12.25";
+
+        assert_eq!(expected, MatrixRowMajor::new(&arr).format_with(2, 2));
+        assert_eq!(expected, MatrixColMajor::new(&arr).format_with(2, 2));
+        assert_eq!(
+            "1 2\n3 4",
+            MatrixBlocky::new(&[vec![1, 2], vec![3, 4]]).format_with(3, 1)
+        );
+    }
+
+    #[test]
+    fn test_saturating_u8() {
+        let a = MatrixRowMajor::<u8>::new(&[vec![200, 100], vec![10, 255]]);
+        let b = MatrixRowMajor::<u8>::new(&[vec![100, 100], vec![5, 1]]);
+        let sum = MatrixRowMajor::new(&[vec![255, 200], vec![15, 255]]);
+        assert!(a.saturating_add(&b).unwrap() == sum);
+
+        let product = MatrixColMajor::<u8>::new(&[vec![16, 255], vec![80, 255]]);
+        let a = MatrixColMajor::<u8>::new(&[vec![2, 200], vec![10, 255]]);
+        let b = MatrixColMajor::<u8>::new(&[vec![8, 100], vec![0, 1]]);
+        assert!(a.saturating_mul(&b).unwrap() == product);
+        assert!(matches!(
+            a.saturating_add(&MatrixColMajor::new(&[vec![1, 2]])),
+            Err(MatrixError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_antidiagonal_and_offsets() {
+        let square = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let row = MatrixRowMajor::new(&square);
+        let col = MatrixColMajor::new(&square);
+
+        assert_eq!(vec![3, 5, 7], row.antidiagonal());
+        assert_eq!(vec![3, 5, 7], col.antidiagonal());
+        assert_eq!(vec![1, 5, 9], row.diagonal_offset(0));
+        assert_eq!(vec![2, 6], row.diagonal_offset(1));
+        assert_eq!(vec![3], col.diagonal_offset(2));
+        assert_eq!(vec![4, 8], col.diagonal_offset(-1));
+        assert_eq!(vec![7], MatrixBlocky::new(&square).diagonal_offset(-2));
+        assert!(row.diagonal_offset(3).is_empty());
+        assert!(row.diagonal_offset(-5).is_empty());
+    }
+
+    #[test]
+    fn test_banded_tridiagonal() {
+        let arr = [
+            vec![2, -1, 0, 0],
+            vec![-1, 2, -1, 0],
+            vec![0, -1, 2, -1],
+            vec![0, 0, -1, 2],
+        ];
+        let band = MatrixBanded::banded(4, 4, 1, 1, |i, j| if i == j { 2 } else { -1 });
+        assert_eq!((1, 1), band.bandwidth());
+        assert_matches_slice(&band, &arr);
+        assert_eq!(2, band.get(2, 2).unwrap());
+        assert_eq!(-1, band.get(1, 0).unwrap());
+        assert_eq!(-1, band.get(2, 3).unwrap());
+        assert_eq!(0, band.get(0, 3).unwrap());
+        assert_eq!(0, band.get(3, 0).unwrap());
+        assert!(band.get(0, 4).is_err());
+
+        let parsed = MatrixBanded::new(&arr);
+        assert_eq!((1, 1), parsed.bandwidth());
+        assert_eq!(band.arr, parsed.arr);
+
+        let mut wide = band.clone();
+        wide.set(0, 3, 7).unwrap();
+        assert_eq!((1, 3), wide.bandwidth());
+        assert_eq!(7, wide.get(0, 3).unwrap());
+        assert_eq!(-1, wide.get(3, 2).unwrap());
+        assert_eq!(vec![5, -4, 1, 0], band.mul(&band).unwrap().row(0).unwrap());
+    }
+
+    #[test]
+    fn test_triangular() {
+        let arr = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+
+        let upper = [vec![1, 2, 3], vec![0, 5, 6], vec![0, 0, 9]];
+        let lower = [vec![1, 0, 0], vec![4, 5, 0], vec![7, 8, 9]];
+        assert_matches_slice(&row.upper_triangular(), &upper);
+        assert_matches_slice(&col.upper_triangular(), &upper);
+        assert_matches_slice(&block.lower_triangular(), &lower);
+        assert_matches_slice(&col.lower_triangular(), &lower);
+
+        assert!(!row.is_upper_triangular());
+        assert!(!row.is_lower_triangular());
+        assert!(row.upper_triangular().is_upper_triangular());
+        assert!(!row.upper_triangular().is_lower_triangular());
+        assert!(col.lower_triangular().is_lower_triangular());
+        assert!(block.lower_triangular().is_lower_triangular());
+        assert!(MatrixRowMajor::<i32>::identity(3).is_upper_triangular());
+        assert!(MatrixRowMajor::<i32>::identity(3).is_lower_triangular());
+    }
+
+    #[test]
+    fn test_flatten() {
+        let arr = [
+            vec![1, 2, 3],
+            vec![4, 5, 6],
+            vec![7, 8, 9],
+            vec![10, 11, 12],
+        ];
+        let flat: Vec<i32> = (1..=12).collect();
+
+        assert_eq!(flat, MatrixRowMajor::new(&arr).flatten());
+        assert_eq!(flat, MatrixColMajor::new(&arr).flatten());
+        assert_eq!(flat, MatrixBlocky::new(&arr).flatten());
+        assert_eq!(flat, MatrixBlocky::with_block_size(&arr, 3).flatten());
+        let row = MatrixRowMajor::from_flat(flat.clone(), 4, 3).unwrap();
+        assert_eq!(flat, row.flatten());
+    }
+
+    #[test]
+    fn test_same_contents() {
+        let arr: Vec<Vec<i32>> = (0..6)
+            .map(|i| (0..6).map(|j| i * 6 + j).collect())
+            .collect();
+        let row = MatrixRowMajor::new(&arr);
+        let block = MatrixBlocky::with_block_size(&arr, 4);
+
+        assert!(same_contents(&block, &row));
+        assert!(same_contents(&row, &MatrixBlocky::new(&arr)));
+        assert!(same_contents(&MatrixSparse::new(&arr), &block));
+
+        let mut changed = block.clone();
+        changed.set(5, 5, 0).unwrap();
+        assert!(!same_contents(&changed, &row));
+        assert!(!same_contents(&row, &row.reshape(4, 9).unwrap()));
+    }
+
+    #[test]
+    fn test_default_get_is_out_of_bounds() {
+        fn assert_empty<M: Matrix<i32>>(mut empty: M) {
+            assert!(empty.is_empty());
+            assert!(matches!(
+                empty.get(0, 0),
+                Err(MatrixError::OutOfBoundIndexing {
+                    i: 0,
+                    j: 0,
+                    offset: 0,
+                    rows: 0,
+                    cols: 0
+                })
+            ));
+            assert!(matches!(
+                empty.set(0, 0, 1),
+                Err(MatrixError::OutOfBoundIndexing { .. })
+            ));
+            assert!(matches!(
+                empty.get_mut(2, 3),
+                Err(MatrixError::OutOfBoundIndexing { .. })
+            ));
+            assert!(matches!(
+                empty.row(0),
+                Err(MatrixError::OutOfBoundIndexing { .. })
+            ));
+            assert!(matches!(
+                empty.col(0),
+                Err(MatrixError::OutOfBoundIndexing { .. })
+            ));
+        }
+
+        assert_empty(<MatrixRowMajor<i32> as Matrix<i32>>::default());
+        assert_empty(<MatrixColMajor<i32> as Matrix<i32>>::default());
+        assert_empty(<MatrixBlocky<i32> as Matrix<i32>>::default());
+        assert_empty(<MatrixSparse<i32> as Matrix<i32>>::default());
+        assert_empty(<MatrixBanded<i32> as Matrix<i32>>::default());
+        assert_empty(MatrixBlocky::<i32>::new(&[]));
+    }
+
+    #[test]
+    fn test_blocky_small() {
+        let one = MatrixBlocky::new(&[vec![7]]);
+        assert_eq!(1, one.arr.len());
+        assert_eq!(7, one.get(0, 0).unwrap());
+        assert_eq!(7, one[(0, 0)]);
+        assert!(one.get(0, 1).is_err());
+        assert!(one.mul(&one).unwrap() == MatrixRowMajor::new(&[vec![49]]));
+
+        let two = MatrixBlocky::new(&[vec![1, 2], vec![3, 4]]);
+        assert_eq!(4, two.arr.len());
+        assert_matches_slice(&two, &[vec![1, 2], vec![3, 4]]);
+
+        let column = MatrixBlocky::new(&[vec![1], vec![2], vec![3]]);
+        assert_eq!(2, column.arr.len());
+        assert_matches_slice(&column, &[vec![1], vec![2], vec![3]]);
+        assert_matches_slice(&column.transposed(), &[vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn test_to_nested_round_trip() {
+        let data: Vec<Vec<i32>> = (0..6)
+            .map(|i| (0..6).map(|j| i * 10 + j).collect())
+            .collect();
+
+        assert_eq!(data, MatrixBlocky::new(&data).to_nested());
+        assert_eq!(data, MatrixBlocky::with_block_size(&data, 4).to_nested());
+        assert_eq!(data, MatrixRowMajor::new(&data).to_nested());
+        assert_eq!(data, MatrixColMajor::new(&data).to_nested());
+        assert_eq!(data, MatrixSparse::new(&data).to_nested());
+        assert!(<MatrixBlocky<i32> as Matrix<i32>>::default()
+            .to_nested()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_with_grid() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..6).map(|j| i * 6 + j).collect())
+            .collect();
+        let block = MatrixBlocky::with_grid(&arr, 2, 3);
+
+        assert_eq!(6, block.arr.len());
+        assert_eq!((2, 2), block.arr[0].shape());
+        for (i, row) in arr.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_eq!(value, block.get(i, j).unwrap());
+            }
+        }
+        assert!(block == MatrixRowMajor::new(&arr));
+        assert!(
+            block.mul(&block.transposed()).unwrap()
+                == MatrixRowMajor::new(&arr)
+                    .mul(&MatrixRowMajor::new(&arr).transposed())
+                    .unwrap()
+        );
+        assert_matches_slice(&MatrixBlocky::with_grid(&arr, 3, 4), &arr);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one block")]
+    fn test_with_grid_empty() {
+        MatrixBlocky::with_grid(&[vec![1, 2]], 0, 1);
+    }
+
+    #[test]
+    fn test_hash_across_layouts() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+
+        fn hash_of<H: Hash>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let arr = [vec![1, 2, 3], vec![4, 5, 6]];
+        let row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+        let block = MatrixBlocky::new(&arr);
+        assert_eq!(hash_of(&row), hash_of(&col));
+        assert_eq!(hash_of(&row), hash_of(&block));
+        assert_ne!(hash_of(&row), hash_of(&row.reshape(3, 2).unwrap()));
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(row.clone()));
+        assert!(!seen.insert(MatrixRowMajor::from(col)));
+        assert!(seen.contains(&MatrixRowMajor::new(&arr)));
+        assert!(seen.insert(row.transposed()));
+        assert_eq!(2, seen.len());
+    }
+
+    #[test]
+    fn test_col_major_matvec_matches_generic() {
+        let arr: Vec<Vec<i64>> = (0..7)
+            .map(|i| (0..5).map(|j| (i * 3 - j * 2) % 7).collect())
+            .collect();
+        let v = [3, -1, 4, 1, -5];
+        // the blocky layout has no specialization of its own
+        let generic = MatrixBlocky::new(&arr).matvec(&v).unwrap();
+        assert_eq!(generic, MatrixColMajor::new(&arr).matvec(&v).unwrap());
+    }
+
+    #[test]
+    fn test_conjugate_transpose() {
+        /// gaussian integer, just enough of a complex number to exercise `Conjugate`
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct Gaussian(i32, i32);
+
+        impl Conjugate for Gaussian {
+            fn conj(self) -> Self {
+                Gaussian(self.0, -self.1)
+            }
+        }
+
+        let c = Gaussian;
+        let arr = [vec![c(1, 2), c(3, -1)], vec![c(0, 4), c(5, 0)]];
+        let hermitian = [vec![c(1, -2), c(0, -4)], vec![c(3, 1), c(5, 0)]];
+
+        let row = MatrixRowMajor::new(&arr);
+        assert!(row.conjugate_transpose() == MatrixRowMajor::new(&hermitian));
+        let col = MatrixColMajor::new(&arr);
+        assert!(col.conjugate_transpose() == MatrixRowMajor::new(&hermitian));
+        assert!(row.conjugate_transpose().conjugate_transpose() == row);
+
+        let real = MatrixBlocky::new(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        assert!(real.conjugate_transpose() == real.transposed());
+    }
+
+    #[test]
+    fn test_frobenius_norm() {
+        let arr = [vec![3.0, 0.0], vec![0.0, 4.0]];
+        assert_eq!(5.0, MatrixRowMajor::new(&arr).frobenius_norm());
+        assert_eq!(5.0, MatrixColMajor::new(&arr).frobenius_norm());
+        assert_eq!(5.0, MatrixBlocky::new(&arr).frobenius_norm());
+
+        let ones = MatrixRowMajor::<f32>::ones(4, 4);
+        assert_eq!(4.0, ones.frobenius_norm());
+        assert_eq!(
+            0.0,
+            <MatrixRowMajor<f64> as Matrix<f64>>::default().frobenius_norm()
+        );
+    }
+
+    #[test]
+    fn test_blocky_new_matches_from_fn() {
+        let arr: Vec<Vec<i32>> = (0..7)
+            .map(|i| (0..5).map(|j| i * 5 + j).collect())
+            .collect();
+        let copied = MatrixBlocky::new(&arr);
+        let built = MatrixBlocky::from(MatrixRowMajor::new(&arr));
+
+        assert_eq!(built.arr.len(), copied.arr.len());
+        for (a, b) in copied.arr.iter().zip(&built.arr) {
+            assert_eq!(a.shape(), b.shape());
+            assert_eq!(a.arr, b.arr);
+        }
+        assert!(copied == built);
+        assert!(MatrixBlocky::try_new(&[vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn test_view() {
+        let arr = square(4);
+        let mut row = MatrixRowMajor::new(&arr);
+        let col = MatrixColMajor::new(&arr);
+
+        for view in [row.view(1..3, 2..4).unwrap(), col.view(1..3, 2..4).unwrap()] {
+            assert_eq!((2, 2), view.shape());
+            assert_eq!(7, view.get(0, 0).unwrap());
+            assert_eq!(8, view.get(0, 1).unwrap());
+            assert_eq!(11, view[(1, 0)]);
+            assert_eq!(12, view[(1, 1)]);
+            assert!(matches!(
+                view.get(0, 2),
+                Err(MatrixError::ForbiddenIndexing { .. })
+            ));
+            assert!(matches!(
+                view.get(2, 0),
+                Err(MatrixError::OutOfBoundIndexing { .. })
+            ));
+        }
+
+        row.set(2, 3, 100).unwrap();
+        assert_eq!(100, row.view(1..3, 2..4).unwrap().get(1, 1).unwrap());
+        assert!(matches!(
+            row.view(3..5, 0..1),
+            Err(MatrixError::InvalidRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut row = MatrixRowMajor::new(&square(4));
+        let mut col = MatrixColMajor::new(&square(4));
+        let expected = [
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            vec![9, 10, 0, -1],
+            vec![13, 14, -10, 160],
+        ];
+
+        {
+            let mut view = row.view_mut(2..4, 2..4).unwrap();
+            assert_eq!((2, 2), view.shape());
+            assert_eq!(11, view.get(0, 0).unwrap());
+            view.set(0, 0, 0).unwrap();
+            view.set(0, 1, -1).unwrap();
+            view[(1, 0)] = -10;
+            *view.get_mut(1, 1).unwrap() *= 10;
+            assert!(view.set(2, 0, 1).is_err());
+            assert!(view.get_mut(0, 2).is_err());
+        }
+        assert_matches_slice(&row, &expected);
+
+        let mut view = col.view_mut(2..4, 2..4).unwrap();
+        view.set(0, 0, 0).unwrap();
+        view.set(0, 1, -1).unwrap();
+        view[(1, 0)] = -10;
+        *view.get_mut(1, 1).unwrap() *= 10;
+        assert_matches_slice(&col, &expected);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let block = MatrixBlocky::new(&square(4));
+        let quarters = [
+            [vec![1, 2], vec![5, 6]],
+            [vec![3, 4], vec![7, 8]],
+            [vec![9, 10], vec![13, 14]],
+            [vec![11, 12], vec![15, 16]],
+        ];
+
+        assert_eq!(4, block.blocks().count());
+        for (b, expected) in block.blocks().zip(&quarters) {
+            assert_matches_slice(b, expected);
+        }
+        assert_matches_slice(block.block_at(0, 1).unwrap(), &quarters[1]);
+        assert_matches_slice(block.block_at(1, 0).unwrap(), &quarters[2]);
+        assert!(matches!(
+            block.block_at(0, 2),
+            Err(MatrixError::BlockIndexing {
+                bi: 0,
+                bj: 2,
+                grid_rows: 2,
+                grid_cols: 2
+            })
+        ));
+        assert_eq!(
+            "Block indexing: block (bi = 2, bj = 1) is outside of the 2x2 grid of blocks",
+            block.block_at(2, 1).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_argmax_argmin() {
+        let arr = [vec![3, -2, 7], vec![0, 9, -5]];
+        assert_eq!(Some((1, 1)), MatrixRowMajor::new(&arr).argmax());
+        assert_eq!(Some((1, 2)), MatrixRowMajor::new(&arr).argmin());
+        assert_eq!(Some((1, 1)), MatrixColMajor::new(&arr).argmax());
+        assert_eq!(Some((1, 2)), MatrixBlocky::new(&arr).argmin());
+
+        let ties = [vec![1, 4, 0], vec![4, 0, 4]];
+        assert_eq!(Some((0, 1)), MatrixColMajor::new(&ties).argmax());
+        assert_eq!(Some((0, 2)), MatrixColMajor::new(&ties).argmin());
+        assert_eq!(Some((0, 1)), MatrixBlocky::new(&ties).argmax());
+
+        let empty = <MatrixRowMajor<i32> as Matrix<i32>>::default();
+        assert_eq!(None, empty.argmax());
+        assert_eq!(None, empty.argmin());
+    }
+
+    #[test]
+    fn test_normalize_rows_cols() {
+        let arr = [vec![3.0, 4.0], vec![0.0, 0.0], vec![1.0, -1.0]];
+        let rows = MatrixRowMajor::new(&arr).normalize_rows();
+        for i in [0, 2] {
+            assert!((rows.row_norm(i).unwrap() - 1.0).abs() < 1e-9);
+        }
+        let half = 0.5f64.sqrt();
+        assert_close(&rows, &[vec![0.6, 0.8], vec![0.0, 0.0], vec![half, -half]]);
+
+        let cols = MatrixColMajor::new(&[vec![3.0, 0.0], vec![4.0, 0.0]]).normalize_cols();
+        assert!((cols.col_norm(0).unwrap() - 1.0).abs() < 1e-9);
+        assert_close(&cols, &[vec![0.6, 0.0], vec![0.8, 0.0]]);
+        assert_close(
+            &MatrixBlocky::new(&arr).normalize_cols(),
+            &[
+                vec![3.0 / 10f64.sqrt(), 4.0 / 17f64.sqrt()],
+                vec![0.0, 0.0],
+                vec![1.0 / 10f64.sqrt(), -1.0 / 17f64.sqrt()],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_clamp() {
+        let arr = [vec![-20, 5, 300], vec![0, 255, 128]];
+        let clamped = [vec![0, 5, 255], vec![0, 255, 128]];
+
+        assert_matches_slice(&MatrixRowMajor::new(&arr).clamp(0, 255), &clamped);
+        assert_matches_slice(&MatrixBlocky::new(&arr).clamp(0, 255), &clamped);
+        let mut col = MatrixColMajor::new(&arr);
+        col.clamp_mut(0, 255);
+        assert_matches_slice(&col, &clamped);
+
+        let floats = MatrixRowMajor::new(&[vec![-1.5, 0.25, 2.0]]).clamp(0.0, 1.0);
+        assert_close(&floats, &[vec![0.0, 0.25, 1.0]]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_from_csv_reader() {
+        let csv = "1,2,3\r\n4, 5 ,\"6\"\n\n\"7\",8,9\n";
+        let row = MatrixRowMajor::<i32>::from_csv_reader(csv.as_bytes()).unwrap();
+        assert_matches_slice(&row, &[vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+
+        let quoted = "\"a,b\",\"say \"\"hi\"\"\"\n\"two\nlines\",c\n";
+        let words = MatrixRowMajor::<String>::from_csv_reader(quoted.as_bytes()).unwrap();
+        assert_eq!("a,b", words[(0, 0)]);
+        assert_eq!("say \"hi\"", words[(0, 1)]);
+        assert_eq!("two\nlines", words[(1, 0)]);
+
+        assert!(matches!(
+            MatrixRowMajor::<i32>::from_csv_reader("1,2,3\n4,5\n".as_bytes()),
+            Err(MatrixError::Jagged(_))
+        ));
+        assert!(matches!(
+            MatrixRowMajor::<i32>::from_csv_reader("1,x\n".as_bytes()),
+            Err(MatrixError::ParseError(_))
+        ));
+        assert!(matches!(
+            MatrixRowMajor::<i32>::from_csv_reader("1,\"2\n".as_bytes()),
+            Err(MatrixError::ParseError(_))
+        ));
+
+        // the spaces inside quotes belong to the value, the ones outside do not
+        let spaced = " a ,\" b \"\r\n";
+        let words = MatrixRowMajor::<String>::from_csv_reader(spaced.as_bytes()).unwrap();
+        assert_eq!("a", words[(0, 0)]);
+        assert_eq!(" b ", words[(0, 1)]);
+        assert!(matches!(
+            MatrixRowMajor::<String>::from_csv_reader("\"x\"y,z\n".as_bytes()),
+            Err(MatrixError::ParseError(_))
+        ));
+        assert!(matches!(
+            MatrixRowMajor::<i32>::from_csv_reader("\"1\" ,2\n".as_bytes()),
+            Err(MatrixError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_mul_empty_lhs_keeps_rhs_cols() {
+        fn check<M: Matrix<i32>>(lhs: M, rhs: M) {
+            assert_eq!((0, 4), lhs.mul(&rhs).unwrap().shape());
+        }
+        let empty: Vec<Vec<i32>> = Vec::new();
+        let rhs = vec![vec![1; 4]; 3];
+
+        check(
+            MatrixRowMajor::from_fn(0, 3, |_, _| 1),
+            MatrixRowMajor::new(&rhs),
+        );
+        check(
+            MatrixColMajor::from_fn(0, 3, |_, _| 1),
+            MatrixColMajor::new(&rhs),
+        );
+        check(
+            MatrixBlocky::from_fn(0, 3, |_, _| 1),
+            MatrixBlocky::new(&rhs),
+        );
+        check(
+            MatrixSparse::from_fn(0, 3, |_, _| 1),
+            MatrixSparse::new(&rhs),
+        );
+        check(
+            MatrixBanded::from_fn(0, 3, |_, _| 1),
+            MatrixBanded::new(&rhs),
+        );
+        assert_eq!(
+            (0, 0),
+            MatrixColMajor::new(&empty)
+                .mul(&MatrixColMajor::new(&empty))
+                .unwrap()
+                .shape()
+        );
+    }
+
+    #[test]
+    fn test_checked_mul_empty_lhs_shape() {
+        let lhs = MatrixRowMajor::<i32>::from_fn(0, 3, |_, _| 1);
+        let rhs = MatrixRowMajor::new(&vec![vec![1; 4]; 3]);
+        assert_eq!(
+            lhs.mul(&rhs).unwrap().shape(),
+            lhs.checked_mul(&rhs).unwrap().shape()
+        );
+
+        let lhs = MatrixBlocky::<i32>::from_fn(0, 3, |_, _| 1);
+        let rhs = MatrixBlocky::new(&vec![vec![1; 4]; 3]);
+        assert_eq!((0, 4), lhs.checked_mul(&rhs).unwrap().shape());
+    }
+
+    #[test]
+    fn test_solve_matches_lu() {
+        // every column needs a row swap, the solution has to undo the permutation of `lu`
+        let a = [
+            vec![1.0, 2.0, 0.0],
+            vec![4.0, 1.0, 3.0],
+            vec![2.0, 7.0, 5.0],
+        ];
+        let b = [vec![5.0], vec![15.0], vec![31.0]];
+        let m = MatrixRowMajor::new(&a);
+
+        let x = m.solve(&MatrixRowMajor::new(&b)).unwrap();
+        assert_close(&x, &[vec![1.0], vec![2.0], vec![3.0]]);
+        assert_close(&m.mul(&x).unwrap(), &b);
+    }
+
+    #[test]
+    fn test_determinant_follows_permutation_sign() {
+        // a cycle of three rows is an even permutation, a single swap an odd one
+        let cycle = [
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+            vec![1.0, 0.0, 0.0],
+        ];
+        let swap = [
+            vec![0.0, 1.0, 0.0],
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+
+        assert!((1.0 - MatrixRowMajor::new(&cycle).determinant().unwrap()).abs() < 1e-9);
+        assert!((-1.0 - MatrixRowMajor::new(&swap).determinant().unwrap()).abs() < 1e-9);
+        // and the inverse of a permutation is its transpose
+        let inverse = MatrixColMajor::new(&cycle).inverse().unwrap();
+        assert_close(
+            &inverse,
+            &[
+                vec![0.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_sparse_mul_matches_dense() {
+        let a = [vec![1, 0, 2, 0], vec![0, 0, 0, 0], vec![0, 3, 0, -1]];
+        let b = [vec![0, 2], vec![1, 0], vec![0, 0], vec![3, 0]];
+
+        let product = MatrixSparse::new(&a).mul(&MatrixSparse::new(&b)).unwrap();
+        let dense = MatrixRowMajor::new(&a)
+            .mul(&MatrixRowMajor::new(&b))
+            .unwrap();
+        assert!(same_contents(&product, &dense));
+        // 3 * 1 + -1 * 3 cancels out and is not kept
+        assert_eq!(1, product.stored());
+        assert_eq!((3, 2), product.shape());
+    }
+
+    #[test]
+    fn test_banded_storage_stays_within_dense() {
+        // a full band on a dense matrix, the slots off the edges are not stored
+        let dense = MatrixBanded::from_fn(5, 3, |i, j| i + j + 1);
+        assert_eq!((4, 2), dense.bandwidth());
+        assert_eq!(15, dense.arr.len());
+        assert!(same_contents(
+            &dense,
+            &MatrixRowMajor::from_fn(5, 3, |i, j| i + j + 1)
+        ));
+
+        let tridiagonal = MatrixBanded::banded(4, 4, 1, 1, |_, _| 1);
+        assert_eq!(10, tridiagonal.arr.len());
+        let product = dense.mul(&dense.transposed()).unwrap();
+        assert_eq!(25, product.arr.len());
+    }
+
+    #[test]
+    fn test_nan_is_singular() {
+        // the NaN sits off the diagonal of `U`, the pivots alone would look fine
+        let a = MatrixRowMajor::new(&[vec![1.0, f64::NAN], vec![0.0, 1.0]]);
+        let b = MatrixRowMajor::new(&[vec![1.0], vec![1.0]]);
+
+        assert!(matches!(a.inverse(), Err(MatrixError::Singular(_))));
+        assert!(matches!(a.solve(&b), Err(MatrixError::Singular(_))));
+        let pivot = MatrixColMajor::new(&[vec![f64::NAN, 0.0], vec![0.0, 1.0]]);
+        assert!(matches!(pivot.inverse(), Err(MatrixError::Singular(_))));
+    }
+
+    #[test]
+    fn test_inverse_checks_the_product() {
+        // the pivots clear the zero tolerance but the inverse is all rounding error
+        let near = MatrixRowMajor::new(&[
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0 + 1e-12],
+        ]);
+
+        assert!(matches!(near.inverse(), Err(MatrixError::Singular(_))));
+    }
+
+    #[test]
+    fn test_with_grid_spreads_evenly() {
+        let arr: Vec<Vec<i32>> = (0..4)
+            .map(|i| (0..5).map(|j| i * 5 + j).collect())
+            .collect();
+        // 4 rows over 3 block rows are 2 + 1 + 1, 5 columns over 3 block columns 2 + 2 + 1
+        let block = MatrixBlocky::with_grid(&arr, 3, 3);
+
+        let shapes: Vec<_> = block.blocks().map(|b| b.shape()).collect();
+        assert_eq!(
+            vec![
+                (2, 2),
+                (2, 2),
+                (2, 1),
+                (1, 2),
+                (1, 2),
+                (1, 1),
+                (1, 2),
+                (1, 2),
+                (1, 1)
+            ],
+            shapes
+        );
+        assert_matches_slice(&block, &arr);
+        assert_eq!(arr[3], block.row(3).unwrap());
+        assert_eq!(vec![4, 9, 14, 19], block.col(4).unwrap());
+        let square = MatrixBlocky::with_grid(&block.transposed().to_nested(), 3, 3);
+        assert!(
+            block.mul(&square).unwrap()
+                == MatrixRowMajor::new(&arr)
+                    .mul(&MatrixRowMajor::new(&arr).transposed())
+                    .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_blocky_resize_keeps_tiling() {
+        let arr: Vec<Vec<i32>> = (0..6)
+            .map(|i| (0..6).map(|j| i * 6 + j).collect())
+            .collect();
+        let mut sized = MatrixBlocky::with_block_size(&arr, 2);
+        sized.resize(7, 5, 0);
+        assert!(sized.blocks().take(2).all(|b| b.shape() == (2, 2)));
+        assert_eq!((4, 3), (sized.grid_rows, sized.grid_cols));
+        assert_eq!(0, sized.get(6, 4).unwrap());
+        assert_eq!(arr[1][..5], sized.row(1).unwrap()[..]);
+
+        let mut grid = MatrixBlocky::with_grid(&arr, 3, 1);
+        grid.resize(8, 6, 0);
+        assert_eq!((3, 1), (grid.grid_rows, grid.grid_cols));
+        assert_eq!(
+            vec![3, 3, 2],
+            grid.blocks().map(|b| b.rows()).collect::<Vec<_>>()
+        );
+
+        let mut empty = <MatrixBlocky<i32> as Matrix<i32>>::default();
+        empty.resize(3, 3, 1);
+        assert_matches_slice(&empty, &[vec![1; 3], vec![1; 3], vec![1; 3]]);
+    }
+}